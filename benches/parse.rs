@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use mtif::MTIFParser;
+
+const ENTRIES: usize = 100_000;
+
+// An export of `entries` entries, each with a few metadata fields, a body
+// and a comment.
+fn corpus(entries: usize) -> String {
+    (0..entries)
+        .map(|i| {
+            format!(
+                "AUTHOR: Author {author}\n\
+                 TITLE: Entry number {i}\n\
+                 BASENAME: entry-{i}\n\
+                 STATUS: Publish\n\
+                 CATEGORY: Category {category}\n\
+                 TAGS: \"tag {tag}\",news\n\
+                 DATE: {month:02}/{day:02}/2002 03:31:05 PM\n\
+                 -----\n\
+                 BODY:\n\
+                 This is the body of entry {i}.\n\
+                 \n\
+                 It has a second paragraph.\n\
+                 -----\n\
+                 COMMENT:\n\
+                 AUTHOR: Commenter {author}\n\
+                 DATE: {month:02}/{day:02}/2002 04:02:07 PM\n\
+                 A comment on entry {i}.\n\
+                 -----\n\
+                 --------\n",
+                author = i % 7,
+                category = i % 13,
+                tag = i % 29,
+                month = i % 12 + 1,
+                day = i % 28 + 1,
+            )
+        })
+        .collect()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let input = corpus(ENTRIES);
+    let parser = MTIFParser::new();
+
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("sequential", |b| b.iter(|| parser.parse(&input).unwrap()));
+    #[cfg(feature = "rayon")]
+    group.bench_function("parallel", |b| {
+        b.iter(|| parser.parse_parallel(&input).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
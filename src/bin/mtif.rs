@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use mtif::wxr::{from_wxr, WXRWriter};
+use mtif::{MTIFReader, MTIFWriter, OwnedMTIFEntry};
+
+#[derive(Parser)]
+#[command(
+    name = "mtif",
+    version,
+    about = "Inspect and convert Movable Type Import Format files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Report every error in FILE with its line number.
+    Validate {
+        /// Input file, or `-` for stdin.
+        file: String,
+    },
+    /// Print entry, comment, ping, author and category counts and the date range.
+    Stats {
+        /// Input file, or `-` for stdin.
+        file: String,
+    },
+    /// Convert FILE, an MTIF or WXR file, to another format on stdout.
+    Convert {
+        #[arg(long, value_enum, default_value = "mtif")]
+        from: InputFormat,
+        #[arg(long, value_enum)]
+        to: Format,
+        /// Input file, or `-` for stdin.
+        file: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// A single JSON array.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Movable Type Import Format.
+    Mtif,
+    /// WordPress eXtended RSS.
+    Wxr,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    /// Movable Type Import Format.
+    Mtif,
+    /// WordPress eXtended RSS.
+    Wxr,
+}
+
+type Entries = Box<dyn Iterator<Item = Result<OwnedMTIFEntry, Box<dyn Error>>>>;
+
+fn open(file: &str) -> io::Result<Box<dyn BufRead>> {
+    if file == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(file)?)))
+    }
+}
+
+// Returns whether the input is free of errors.
+fn validate(file: &str) -> Result<bool, Box<dyn Error>> {
+    let mut entries = 0;
+    let mut errors = 0;
+    for entry in MTIFReader::new(open(file)?) {
+        match entry {
+            Ok(_) => entries += 1,
+            Err(e) => {
+                errors += 1;
+                eprintln!("{}: {}", file, e);
+            }
+        }
+    }
+
+    println!("{}: {} entries, {} errors", file, entries, errors);
+    Ok(errors == 0)
+}
+
+#[derive(Default)]
+struct Stats {
+    entries: usize,
+    comments: usize,
+    pings: usize,
+    authors: BTreeMap<String, usize>,
+    categories: BTreeMap<String, usize>,
+    first_date: Option<time::OffsetDateTime>,
+    last_date: Option<time::OffsetDateTime>,
+}
+
+impl Stats {
+    fn add(&mut self, entry: &OwnedMTIFEntry) {
+        self.entries += 1;
+        self.comments += entry.comments.len();
+        self.pings += entry.pings.len();
+        if let Some(author) = &entry.metadata.author {
+            *self.authors.entry(author.clone()).or_default() += 1;
+        }
+        for category in &entry.metadata.category {
+            *self.categories.entry(category.clone()).or_default() += 1;
+        }
+
+        // Dates compare as instants, whatever their offsets.
+        let date = entry.metadata.date.assume_offset(entry.metadata.utc_offset);
+        self.first_date = Some(self.first_date.map_or(date, |first| first.min(date)));
+        self.last_date = Some(self.last_date.map_or(date, |last| last.max(date)));
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date_format = time::macros::format_description!(
+            "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]:[offset_minute]"
+        );
+
+        writeln!(f, "entries: {}", self.entries)?;
+        writeln!(f, "comments: {}", self.comments)?;
+        writeln!(f, "pings: {}", self.pings)?;
+        writeln!(f, "authors: {}", self.authors.len())?;
+        for (author, count) in &self.authors {
+            writeln!(f, "  {}: {}", author, count)?;
+        }
+        writeln!(f, "categories: {}", self.categories.len())?;
+        for (category, count) in &self.categories {
+            writeln!(f, "  {}: {}", category, count)?;
+        }
+        if let (Some(first), Some(last)) = (self.first_date, self.last_date) {
+            writeln!(
+                f,
+                "dates: {} to {}",
+                first.format(date_format).map_err(|_| fmt::Error)?,
+                last.format(date_format).map_err(|_| fmt::Error)?
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn stats(file: &str) -> Result<bool, Box<dyn Error>> {
+    let mut stats = Stats::default();
+    for entry in MTIFReader::new(open(file)?) {
+        stats.add(&entry?);
+    }
+
+    print!("{}", stats);
+    Ok(true)
+}
+
+fn read_entries(file: &str, from: InputFormat) -> Result<Entries, Box<dyn Error>> {
+    Ok(match from {
+        InputFormat::Mtif => Box::new(MTIFReader::new(open(file)?).map(|entry| Ok(entry?))),
+        InputFormat::Wxr => Box::new(from_wxr(open(file)?)?.into_iter().map(Ok)),
+    })
+}
+
+fn convert(file: &str, from: InputFormat, to: Format) -> Result<bool, Box<dyn Error>> {
+    let entries = read_entries(file, from)?;
+    let mut output = BufWriter::new(io::stdout().lock());
+
+    match to {
+        Format::Json => {
+            let entries = entries.collect::<Result<Vec<_>, _>>()?;
+            serde_json::to_writer_pretty(&mut output, &entries)?;
+            writeln!(output)?;
+        }
+        Format::Ndjson => {
+            for entry in entries {
+                serde_json::to_writer(&mut output, &entry?)?;
+                writeln!(output)?;
+            }
+        }
+        Format::Wxr => {
+            let entries = entries.collect::<Result<Vec<_>, _>>()?;
+            let entries: Vec<_> = entries.iter().map(OwnedMTIFEntry::as_borrowed).collect();
+            WXRWriter::new().write(&mut output, &entries)?;
+        }
+        Format::Mtif => {
+            let writer = MTIFWriter::new();
+            for entry in entries {
+                writer.write_entry(&mut output, &entry?.as_borrowed())?;
+            }
+        }
+    }
+
+    output.flush()?;
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Validate { file } => validate(file),
+        Command::Stats { file } => stats(file),
+        Command::Convert { from, to, file } => convert(file, *from, *to),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("mtif: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtif::{DateFormat, MTIFParser, ParseOptions};
+
+    #[test]
+    fn test_stats() {
+        let file = File::open("./example/example.txt").unwrap();
+        let mut stats = Stats::default();
+        for entry in MTIFReader::new(BufReader::new(file)) {
+            stats.add(&entry.unwrap());
+        }
+
+        assert_eq!(
+            stats.to_string(),
+            "entries: 2\n\
+             comments: 3\n\
+             pings: 1\n\
+             authors: 2\n  \
+             Baz Quux: 1\n  \
+             Foo Bar: 1\n\
+             categories: 2\n  \
+             News: 1\n  \
+             Politics: 1\n\
+             dates: 2002-01-31 03:31:05 +00:00 to 2002-01-31 15:31:05 +00:00\n"
+        );
+    }
+
+    #[test]
+    fn test_stats_offsets() {
+        let contents = "DATE: 2004-01-05T15:00:00+09:00\n-----\n--------\n\
+                        DATE: 2004-01-05T10:00:00Z\n-----\n--------\n";
+        let parser =
+            MTIFParser::with_options(ParseOptions::new().date_formats(&[DateFormat::Iso8601]));
+        let mut stats = Stats::default();
+        for entry in parser.parse(contents).unwrap() {
+            stats.add(&entry.into_owned());
+        }
+
+        assert!(stats
+            .to_string()
+            .ends_with("dates: 2004-01-05 15:00:00 +09:00 to 2004-01-05 10:00:00 +00:00\n"));
+    }
+}
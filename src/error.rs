@@ -0,0 +1,134 @@
+use std::{fmt, io, ops::Range};
+
+/// Where in the input a [`MTIFError`] was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// 0-based index of the entry being parsed.
+    pub entry: usize,
+    /// The offending line, without its line ending.
+    pub source_line: String,
+}
+
+#[derive(Debug)]
+pub enum MTIFError {
+    /// A line that is not a known field at this point of the entry.
+    UnexpectedField(ErrorLocation),
+    /// The entry has no `DATE:` field in its metadata section.
+    MissingDate(ErrorLocation),
+    /// A `DATE:` value that could not be parsed.
+    InvalidDate(ErrorLocation),
+    /// The input ended before a `-----` or `--------` separator.
+    MissingSeparator(ErrorLocation),
+    /// Something other than a new entry follows an entry terminator.
+    TrailingInput(ErrorLocation),
+    /// Reading the input failed.
+    Io(io::Error),
+}
+
+impl MTIFError {
+    /// Returns `None` for I/O errors, which have no position in the input.
+    pub fn location(&self) -> Option<&ErrorLocation> {
+        match self {
+            MTIFError::UnexpectedField(location)
+            | MTIFError::MissingDate(location)
+            | MTIFError::InvalidDate(location)
+            | MTIFError::MissingSeparator(location)
+            | MTIFError::TrailingInput(location) => Some(location),
+            MTIFError::Io(_) => None,
+        }
+    }
+
+    fn location_mut(&mut self) -> Option<&mut ErrorLocation> {
+        match self {
+            MTIFError::UnexpectedField(location)
+            | MTIFError::MissingDate(location)
+            | MTIFError::InvalidDate(location)
+            | MTIFError::MissingSeparator(location)
+            | MTIFError::TrailingInput(location) => Some(location),
+            MTIFError::Io(_) => None,
+        }
+    }
+
+    /// Rebases a location computed on a slice of the input that starts
+    /// after `lines` lines and `entries` entries.
+    pub(crate) fn offset_by(mut self, lines: usize, entries: usize) -> Self {
+        if let Some(location) = self.location_mut() {
+            location.line += lines;
+            location.entry += entries;
+        }
+        self
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            MTIFError::UnexpectedField(_) => "unexpected field",
+            MTIFError::MissingDate(_) => "entry has no DATE field",
+            MTIFError::InvalidDate(_) => "invalid date",
+            MTIFError::MissingSeparator(_) => "missing separator",
+            MTIFError::TrailingInput(_) => "trailing input after last entry",
+            MTIFError::Io(_) => "I/O error",
+        }
+    }
+}
+
+// I/O errors compare by kind, since `io::Error` itself is not comparable.
+impl PartialEq for MTIFError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MTIFError::Io(a), MTIFError::Io(b)) => a.kind() == b.kind(),
+            (a, b) => {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+                    && a.location() == b.location()
+            }
+        }
+    }
+}
+
+impl fmt::Display for MTIFError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = match self {
+            MTIFError::Io(e) => return write!(f, "{}: {}", self.description(), e),
+            _ => self.location().expect("non-I/O errors have a location"),
+        };
+        write!(
+            f,
+            "{} at line {}, column {} (entry {}): {:?}",
+            self.description(),
+            location.line,
+            location.column,
+            location.entry,
+            location.source_line
+        )
+    }
+}
+
+impl std::error::Error for MTIFError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MTIFError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MTIFError {
+    fn from(e: io::Error) -> Self {
+        MTIFError::Io(e)
+    }
+}
+
+/// An entry skipped by [`MTIFParser::parse_recovering`](crate::MTIFParser::parse_recovering).
+#[derive(Debug, PartialEq)]
+pub struct BrokenEntry {
+    /// 0-based index of the entry, counting both good and broken entries.
+    pub entry: usize,
+    /// 1-based lines that were skipped, up to and including the `--------`
+    /// line the parser resynchronised on, or up to the line before the next
+    /// entry when the lines between two entries were skipped.
+    pub lines: Range<usize>,
+    pub error: MTIFError,
+}
@@ -0,0 +1,388 @@
+//! Export to Atom 1.0 or RSS 2.0 feeds.
+//!
+//! Draft entries and entries marked `NO ENTRY` are left out. The others are
+//! written newest first, with their text rendered as HTML by
+//! [`render_entry`]; text in a format that cannot be rendered is included
+//! as it is.
+
+use std::io;
+
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::Writer;
+
+use crate::model::Status;
+use crate::render::{render_entry, RenderedEntry};
+use crate::xml::{format_error, rfc2822, write_cdata, write_text};
+use crate::MTIFEntry;
+
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+
+const RSS_NAMESPACES: [(&str, &str); 2] = [
+    ("xmlns:content", "http://purl.org/rss/1.0/modules/content/"),
+    ("xmlns:dc", "http://purl.org/dc/elements/1.1/"),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum FeedFormat {
+    #[default]
+    Atom,
+    Rss,
+}
+
+pub struct FeedWriter {
+    format: FeedFormat,
+    title: String,
+    description: String,
+    base_url: String,
+    permalink: String,
+}
+
+impl Default for FeedWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FeedWriter {
+    pub fn new() -> Self {
+        FeedWriter {
+            format: FeedFormat::default(),
+            title: String::new(),
+            description: String::new(),
+            base_url: String::new(),
+            permalink: "{year}/{month}/{basename}.html".to_string(),
+        }
+    }
+
+    pub fn format(mut self, format: FeedFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// The Atom subtitle or RSS channel description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// The site URL, used as the feed link and id and as the base of entry
+    /// permalinks.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The path of an entry relative to [`base_url`](Self::base_url).
+    /// `{year}`, `{month}`, `{day}` and `{basename}` are replaced with the
+    /// entry's values; entries without `BASENAME` use a slug of their
+    /// title. Defaults to `{year}/{month}/{basename}.html`.
+    pub fn permalink(mut self, permalink: impl Into<String>) -> Self {
+        self.permalink = permalink.into();
+        self
+    }
+
+    /// The URL of `entry` built from the base URL and permalink template.
+    pub fn entry_url(&self, entry: &MTIFEntry<'_>) -> String {
+        let date = entry.metadata.date;
+        let basename = entry
+            .metadata
+            .basename
+            .map_or_else(|| crate::static_site::slug(entry), str::to_string);
+        let path = self
+            .permalink
+            .replace("{year}", &format!("{:04}", date.year()))
+            .replace("{month}", &format!("{:02}", u8::from(date.month())))
+            .replace("{day}", &format!("{:02}", date.day()))
+            .replace("{basename}", &basename);
+
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    pub fn write<W: io::Write>(&self, writer: W, entries: &[MTIFEntry<'_>]) -> io::Result<()> {
+        let mut entries: Vec<&MTIFEntry<'_>> = entries
+            .iter()
+            .filter(|entry| {
+                !entry.metadata.no_entry && entry.metadata.status != Some(Status::Draft)
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.metadata.date_utc()));
+
+        let mut writer = Writer::new_with_indent(writer, b' ', 2);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        match self.format {
+            FeedFormat::Atom => self.write_atom(&mut writer, &entries)?,
+            FeedFormat::Rss => self.write_rss(&mut writer, &entries)?,
+        }
+        writeln!(writer.get_mut())
+    }
+
+    fn write_atom<W: io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+        entries: &[&MTIFEntry<'_>],
+    ) -> io::Result<()> {
+        // An empty feed has nothing to date it by.
+        let updated = entries
+            .first()
+            .map_or(time::OffsetDateTime::UNIX_EPOCH, |entry| {
+                entry.metadata.date_with_offset()
+            });
+
+        writer
+            .create_element("feed")
+            .with_attribute(("xmlns", ATOM_NAMESPACE))
+            .write_inner_content(|writer| {
+                write_text(writer, "title", &self.title)?;
+                if !self.description.is_empty() {
+                    write_text(writer, "subtitle", &self.description)?;
+                }
+                write_link(writer, &self.base_url)?;
+                write_text(writer, "id", &self.base_url)?;
+                write_text(writer, "updated", &rfc3339(updated)?)?;
+                for entry in entries {
+                    writer
+                        .create_element("entry")
+                        .write_inner_content(|writer| self.write_atom_entry(writer, entry))?;
+                }
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    fn write_atom_entry<W: io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+        entry: &MTIFEntry<'_>,
+    ) -> io::Result<()> {
+        let metadata = &entry.metadata;
+        let url = self.entry_url(entry);
+        let date = rfc3339(metadata.date_with_offset())?;
+
+        write_text(writer, "title", metadata.title.unwrap_or_default())?;
+        write_link(writer, &url)?;
+        write_text(writer, "id", &url)?;
+        write_text(writer, "published", &date)?;
+        write_text(writer, "updated", &date)?;
+        if let Some(author) = metadata.author {
+            writer
+                .create_element("author")
+                .write_inner_content(|writer| write_text(writer, "name", author))?;
+        }
+        for category in categories(entry) {
+            writer
+                .create_element("category")
+                .with_attribute(("term", category))
+                .write_empty()?;
+        }
+        if let Some(excerpt) = entry.excerpt {
+            write_text(writer, "summary", excerpt)?;
+        }
+        let (content, content_type) = match content(entry) {
+            Some(html) => (html, "html"),
+            None => (raw_content(entry), "text"),
+        };
+        writer
+            .create_element("content")
+            .with_attribute(("type", content_type))
+            .write_text_content(BytesText::new(&content))?;
+        Ok(())
+    }
+
+    fn write_rss<W: io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+        entries: &[&MTIFEntry<'_>],
+    ) -> io::Result<()> {
+        writer
+            .create_element("rss")
+            .with_attribute(("version", "2.0"))
+            .with_attributes(RSS_NAMESPACES)
+            .write_inner_content(|writer| {
+                writer
+                    .create_element("channel")
+                    .write_inner_content(|writer| {
+                        write_text(writer, "title", &self.title)?;
+                        write_text(writer, "link", &self.base_url)?;
+                        write_text(writer, "description", &self.description)?;
+                        for entry in entries {
+                            writer
+                                .create_element("item")
+                                .write_inner_content(|writer| self.write_rss_item(writer, entry))?;
+                        }
+                        Ok(())
+                    })?;
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    fn write_rss_item<W: io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+        entry: &MTIFEntry<'_>,
+    ) -> io::Result<()> {
+        let metadata = &entry.metadata;
+        let url = self.entry_url(entry);
+
+        write_text(writer, "title", metadata.title.unwrap_or_default())?;
+        write_text(writer, "link", &url)?;
+        writer
+            .create_element("guid")
+            .with_attribute(("isPermaLink", "true"))
+            .write_text_content(BytesText::new(&url))?;
+        write_text(writer, "pubDate", &rfc2822(metadata.date_with_offset())?)?;
+        if let Some(author) = metadata.author {
+            write_text(writer, "dc:creator", author)?;
+        }
+        for category in categories(entry) {
+            write_text(writer, "category", category)?;
+        }
+        if let Some(excerpt) = entry.excerpt {
+            write_text(writer, "description", excerpt)?;
+        }
+        let content = content(entry).unwrap_or_else(|| raw_content(entry));
+        write_cdata(writer, "content:encoded", &content)
+    }
+}
+
+fn write_link<W: io::Write>(writer: &mut Writer<W>, href: &str) -> io::Result<()> {
+    writer
+        .create_element("link")
+        .with_attribute(("rel", "alternate"))
+        .with_attribute(("href", href))
+        .write_empty()?;
+    Ok(())
+}
+
+// Categories, with the primary one first, then tags.
+fn categories<'a>(entry: &'a MTIFEntry<'_>) -> impl Iterator<Item = &'a str> {
+    let metadata = &entry.metadata;
+    let primary_category = metadata
+        .primary_category
+        .filter(|primary_category| !metadata.category.contains(primary_category));
+    primary_category
+        .into_iter()
+        .chain(metadata.category.iter().copied())
+        .chain(metadata.tags.iter().copied())
+}
+
+// `None` when the entry's text format cannot be rendered.
+fn content(entry: &MTIFEntry<'_>) -> Option<String> {
+    let RenderedEntry {
+        body,
+        extended_body,
+        ..
+    } = render_entry(entry).ok()?;
+    Some(join(body.as_deref(), extended_body.as_deref()))
+}
+
+fn raw_content(entry: &MTIFEntry<'_>) -> String {
+    join(entry.body, entry.extended_body)
+}
+
+fn join(body: Option<&str>, extended_body: Option<&str>) -> String {
+    let body = body.unwrap_or_default();
+    match extended_body {
+        Some(extended_body) => format!("{}\n\n{}", body, extended_body),
+        None => body.to_string(),
+    }
+}
+
+fn rfc3339(date: time::OffsetDateTime) -> io::Result<String> {
+    date.format(&time::format_description::well_known::Rfc3339)
+        .map_err(format_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MTIFParser;
+
+    fn entries(contents: &str) -> Vec<MTIFEntry<'_>> {
+        MTIFParser::new().parse(contents).unwrap()
+    }
+
+    fn write_to_string(writer: &FeedWriter, entries: &[MTIFEntry<'_>]) -> String {
+        let mut output = Vec::new();
+        writer.write(&mut output, entries).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn feed_writer() -> FeedWriter {
+        FeedWriter::new()
+            .title("Example")
+            .base_url("https://example.com/")
+    }
+
+    #[test]
+    fn test_write_atom() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        insta::assert_snapshot!(write_to_string(&feed_writer(), &entries(&contents)));
+    }
+
+    #[test]
+    fn test_write_rss() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        insta::assert_snapshot!(write_to_string(
+            &feed_writer().format(FeedFormat::Rss),
+            &entries(&contents)
+        ));
+    }
+
+    #[test]
+    fn test_skipped_entries() {
+        let contents = "TITLE: Draft\n\
+                        STATUS: Draft\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        --------\n\
+                        TITLE: Hidden\n\
+                        NO ENTRY: 1\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        --------\n\
+                        TITLE: Published\n\
+                        CONVERT BREAKS: textile_2\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        BODY:\n\
+                        *strong*\n\
+                        -----\n\
+                        --------\n";
+        let output = write_to_string(&feed_writer(), &entries(contents));
+
+        assert!(!output.contains("Draft"));
+        assert!(!output.contains("Hidden"));
+        assert!(output.contains("<title>Published</title>"));
+        assert!(output.contains("<content type=\"text\">*strong*</content>"));
+    }
+
+    #[test]
+    fn test_entry_url() {
+        let contents = "TITLE: Hello, World\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        --------\n";
+        let entries = entries(contents);
+
+        assert_eq!(
+            feed_writer().entry_url(&entries[0]),
+            "https://example.com/2002/01/hello-world.html"
+        );
+        assert_eq!(
+            feed_writer()
+                .permalink("/archives/{year}{month}{day}/{basename}/")
+                .entry_url(&entries[0]),
+            "https://example.com/archives/20020131/hello-world/"
+        );
+    }
+}
@@ -35,9 +35,12 @@
 //! 						"News",
 //! 				],
 //! 				date: 2002-01-31 15:31:05.0,
+//! 				utc_offset: +00:00:00,
 //! 				no_entry: false,
 //! 				tags: [],
 //! 				image: None,
+//! 				unique_url: None,
+//! 				extra: [],
 //! 		},
 //! 		body: Some(
 //! 				"This is the body.\n\nAnother paragraph here.\n\nAnother paragraph here.",
@@ -58,6 +61,8 @@
 //! 						date: Some(
 //! 								2002-01-31 15:47:06.0,
 //! 						),
+//! 						utc_offset: +00:00:00,
+//! 						status: None,
 //! 						text: "This is\nthe body of this comment.",
 //! 				},
 //! 				Comment {
@@ -74,6 +79,8 @@
 //! 						date: Some(
 //! 								2002-02-01 4:02:07.0,
 //! 						),
+//! 						utc_offset: +00:00:00,
+//! 						status: None,
 //! 						text: "This is the body of\nanother comment. It goes\nup to here.",
 //! 				},
 //! 		],
@@ -91,12 +98,16 @@
 //! 						date: Some(
 //! 								2002-08-05 16:09:12.0,
 //! 						),
+//! 						utc_offset: +00:00:00,
 //! 						blog_name: Some(
 //! 								"My Weblog",
 //! 						),
+//! 						status: None,
 //! 						text: "This is the start of my\nentry, and here it...",
 //! 				},
 //! 		],
+//! 		pinged_urls: [],
+//! 		extra_sections: [],
 //! 	},
 //! 	MTIFEntry {
 //! 		metadata: MetaData {
@@ -118,9 +129,12 @@
 //! 						"Politics",
 //! 				],
 //! 				date: 2002-01-31 3:31:05.0,
+//! 				utc_offset: +00:00:00,
 //! 				no_entry: false,
 //! 				tags: [],
 //! 				image: None,
+//! 				unique_url: None,
+//! 				extra: [],
 //! 		},
 //! 		body: Some(
 //! 				"This is the body of the second entry. It can\nconsist of multiple lines.",
@@ -143,183 +157,329 @@
 //! 						date: Some(
 //! 								2002-01-31 16:23:01.0,
 //! 						),
+//! 						utc_offset: +00:00:00,
+//! 						status: None,
 //! 						text: "Here is the first comment on this entry.",
 //! 				},
 //! 		],
 //! 		pings: [],
+//! 		pinged_urls: [],
+//! 		extra_sections: [],
 //! 	},
 //! ]
 
+// The example output in the crate docs above is indented with tabs.
+#![allow(clippy::tabs_in_doc_comments)]
+
+pub mod error;
+#[cfg(feature = "feed")]
+pub mod feed;
+pub mod lint;
 pub mod model;
+mod options;
 mod parser;
+mod reader;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod span;
+pub mod static_site;
+mod writer;
+#[cfg(feature = "wxr")]
+pub mod wxr;
+#[cfg(any(feature = "feed", feature = "wxr"))]
+mod xml;
 
-pub use model::MTIFEntry;
+pub use error::{BrokenEntry, MTIFError};
 use model::{Comment, MetaData, Ping};
+pub use model::{MTIFEntry, OwnedMTIFEntry};
+pub use options::{DateFormat, Dialect, ParseOptions, UnknownFields};
+pub use reader::MTIFReader;
+pub use writer::MTIFWriter;
+
+// Inputs are not split into pieces smaller than this.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_LEN: usize = 64 * 1024;
 
-pub struct MTIFParser {}
+#[derive(Default)]
+pub struct MTIFParser {
+    options: ParseOptions,
+}
 
 impl MTIFParser {
     pub fn new() -> Self {
-        MTIFParser {}
+        MTIFParser {
+            options: ParseOptions::default(),
+        }
+    }
+
+    pub fn with_options(options: ParseOptions) -> Self {
+        MTIFParser { options }
     }
 
-    pub fn parse<'a>(&self, input: &'a str) -> Result<Vec<MTIFEntry<'a>>, String> {
-        let (_, entries) = parser::parse_mtif(input).map_err(|e| e.to_string())?;
+    pub fn parse<'a>(&self, input: &'a str) -> Result<Vec<MTIFEntry<'a>>, MTIFError> {
+        let entries = parser::parse_mtif(input, &self.options)?;
+
+        Ok(entries
+            .iter()
+            .map(|e| self.build_mtif_entry_from_raw_mtif_entry(e))
+            .collect())
+    }
+
+    /// Like [`parse`](Self::parse), but splits the input between entries and
+    /// parses the pieces on the rayon thread pool. Entries come back in input
+    /// order, and errors are the same as `parse` reports.
+    #[cfg(feature = "rayon")]
+    pub fn parse_parallel<'a>(&self, input: &'a str) -> Result<Vec<MTIFEntry<'a>>, MTIFError> {
+        use rayon::prelude::*;
+
+        let parts = (input.len() / PARALLEL_CHUNK_LEN).clamp(1, rayon::current_num_threads() * 4);
+        let chunks = parser::split_entries(input, parts);
+        let entries: Result<Vec<Vec<MTIFEntry<'a>>>, MTIFError> = chunks
+            .par_iter()
+            .map(|chunk| {
+                Ok(parser::parse_mtif(chunk, &self.options)?
+                    .iter()
+                    .map(|e| self.build_mtif_entry_from_raw_mtif_entry(e))
+                    .collect())
+            })
+            .collect();
+
+        match entries {
+            Ok(entries) => Ok(entries.into_iter().flatten().collect()),
+            // A chunk's error is relative to the chunk, and it may not be the
+            // first one in the input; parsing again finds the one `parse` would.
+            Err(_) => self.parse(input),
+        }
+    }
+
+    /// Parses as many entries as possible. When an entry is malformed, the
+    /// parser skips to the line after the next `--------` and goes on; lines
+    /// between entries that cannot start one are skipped up to the next line
+    /// that can. The skipped entries are returned alongside the good ones.
+    pub fn parse_recovering<'a>(&self, input: &'a str) -> (Vec<MTIFEntry<'a>>, Vec<BrokenEntry>) {
+        let (entries, errors) = parser::parse_mtif_recovering(input, &self.options);
+        let line_index = span::LineIndex::new(input);
 
         let entries = entries
             .iter()
             .map(|e| self.build_mtif_entry_from_raw_mtif_entry(e))
             .collect();
+        let broken = errors
+            .into_iter()
+            .map(|(entry, skipped, error)| {
+                let span = line_index.span(skipped);
+                BrokenEntry {
+                    entry,
+                    lines: span.start.line..span.end.line + 1,
+                    error,
+                }
+            })
+            .collect();
 
-        entries
+        (entries, broken)
+    }
+
+    /// Like [`parse`](Self::parse), also returning where each entry and
+    /// field is in `input`.
+    pub fn parse_with_spans<'a>(
+        &self,
+        input: &'a str,
+    ) -> Result<Vec<(MTIFEntry<'a>, span::EntrySpans<'a>)>, MTIFError> {
+        let entries = parser::parse_mtif(input, &self.options)?;
+        let line_index = span::LineIndex::new(input);
+
+        Ok(entries
+            .iter()
+            .map(|e| {
+                let spans = span::EntrySpans {
+                    entry: line_index.span(e.source),
+                    metadata: e
+                        .metadata_sources
+                        .iter()
+                        .map(|source| line_index.field_span(source))
+                        .collect(),
+                    multiline: e
+                        .multiline_sources
+                        .iter()
+                        .map(|source| line_index.field_span(source))
+                        .collect(),
+                };
+                (self.build_mtif_entry_from_raw_mtif_entry(e), spans)
+            })
+            .collect())
     }
 
+    // Each raw field is folded into the entry in one pass. Of fields that
+    // hold a single value, the first one wins; repeatable fields are kept in
+    // input order.
     fn build_mtif_entry_from_raw_mtif_entry<'a>(
         &self,
         raw_mtif_entry: &parser::RawMTIFEntry<'a>,
-    ) -> Result<MTIFEntry<'a>, String> {
-        let metadata = self.build_metadata_from_raw_entry(raw_mtif_entry)?;
-
-        Ok(MTIFEntry {
-            metadata,
-            body: raw_mtif_entry.multiline_data.iter().find_map(|m| match m {
-                parser::MultiLineField::Body(body) => Some(*body),
-                _ => None,
-            }),
-            extended_body: raw_mtif_entry.multiline_data.iter().find_map(|m| match m {
-                parser::MultiLineField::ExtendedBody(extended_body) => Some(*extended_body),
-                _ => None,
-            }),
-            excerpt: raw_mtif_entry.multiline_data.iter().find_map(|m| match m {
-                parser::MultiLineField::Excerpt(excerpt) => Some(*excerpt),
-                _ => None,
-            }),
-            keywords: raw_mtif_entry.multiline_data.iter().find_map(|m| match m {
-                parser::MultiLineField::Keywords(keywords) => Some(*keywords),
-                _ => None,
-            }),
-            comments: raw_mtif_entry
-                .multiline_data
-                .iter()
-                .filter_map(|m| match m {
-                    parser::MultiLineField::Comment {
-                        author,
-                        email,
-                        url,
-                        ip,
-                        date,
-                        text,
-                    } => Some(Comment {
-                        author: *author,
-                        email: *email,
-                        url: *url,
-                        ip: *ip,
-                        date: *date,
-                        text: *text,
-                    }),
-                    _ => None,
-                })
-                .collect(),
-            pings: raw_mtif_entry
-                .multiline_data
-                .iter()
-                .filter_map(|m| match m {
-                    parser::MultiLineField::Ping {
-                        title,
-                        url,
-                        ip,
-                        date,
-                        blog_name,
-                        text,
-                    } => Some(Ping {
-                        title: *title,
-                        url: *url,
-                        ip: *ip,
-                        date: *date,
-                        blog_name: *blog_name,
-                        text: *text,
-                    }),
-                    _ => None,
-                })
-                .collect(),
-        })
+    ) -> MTIFEntry<'a> {
+        let mut entry = MTIFEntry {
+            metadata: self.build_metadata_from_raw_entry(raw_mtif_entry),
+            body: None,
+            extended_body: None,
+            excerpt: None,
+            keywords: None,
+            comments: vec![],
+            pings: vec![],
+            pinged_urls: vec![],
+            extra_sections: vec![],
+        };
+
+        for field in &raw_mtif_entry.multiline_data {
+            match field {
+                parser::MultiLineField::Body(body) => {
+                    entry.body.get_or_insert(*body);
+                }
+                parser::MultiLineField::ExtendedBody(extended_body) => {
+                    entry.extended_body.get_or_insert(*extended_body);
+                }
+                parser::MultiLineField::Excerpt(excerpt) => {
+                    entry.excerpt.get_or_insert(*excerpt);
+                }
+                parser::MultiLineField::Keywords(keywords) => {
+                    entry.keywords.get_or_insert(*keywords);
+                }
+                parser::MultiLineField::Comment {
+                    author,
+                    email,
+                    url,
+                    ip,
+                    date,
+                    utc_offset,
+                    status,
+                    text,
+                } => entry.comments.push(Comment {
+                    author: *author,
+                    email: *email,
+                    url: *url,
+                    ip: *ip,
+                    date: *date,
+                    utc_offset: utc_offset.unwrap_or(self.options.utc_offset()),
+                    status: *status,
+                    text,
+                }),
+                parser::MultiLineField::Ping {
+                    title,
+                    url,
+                    ip,
+                    date,
+                    utc_offset,
+                    blog_name,
+                    status,
+                    text,
+                } => entry.pings.push(Ping {
+                    title: *title,
+                    url: *url,
+                    ip: *ip,
+                    date: *date,
+                    utc_offset: utc_offset.unwrap_or(self.options.utc_offset()),
+                    blog_name: *blog_name,
+                    status: *status,
+                    text,
+                }),
+                parser::MultiLineField::PingedUrls(urls) => entry.pinged_urls.extend(urls),
+                parser::MultiLineField::Unknown(name, text) => {
+                    if self.keeps_unknown_fields() {
+                        entry.extra_sections.push((*name, *text));
+                    }
+                }
+            }
+        }
+
+        entry
     }
 
     fn build_metadata_from_raw_entry<'a>(
         &self,
         raw_mtif_entry: &parser::RawMTIFEntry<'a>,
-    ) -> Result<MetaData<'a>, String> {
-        let ref raw_metadata = raw_mtif_entry.metadata;
-        let date_value = raw_metadata.iter().find_map(|m| match m {
-            parser::MetaDataField::Date(date) => Some(*date),
-            _ => None,
-        });
-        if date_value.is_none() {
-            return Err("Date is required".to_string());
+    ) -> MetaData<'a> {
+        let (date, utc_offset) = raw_mtif_entry.date;
+        let mut metadata = MetaData {
+            author: None,
+            title: None,
+            basename: None,
+            status: None,
+            allow_comments: None,
+            allow_pings: None,
+            convert_breaks: None,
+            primary_category: None,
+            category: vec![],
+            date,
+            utc_offset: utc_offset.unwrap_or(self.options.utc_offset()),
+            no_entry: false,
+            tags: vec![],
+            image: None,
+            unique_url: None,
+            extra: vec![],
+        };
+        let mut tags = None;
+
+        for field in &raw_mtif_entry.metadata {
+            match field {
+                parser::MetaDataField::Author(author) => {
+                    metadata.author.get_or_insert(*author);
+                }
+                parser::MetaDataField::Title(title) => {
+                    metadata.title.get_or_insert(*title);
+                }
+                parser::MetaDataField::BaseName(basename) => {
+                    metadata.basename.get_or_insert(*basename);
+                }
+                parser::MetaDataField::Status(status) => {
+                    metadata.status.get_or_insert(*status);
+                }
+                parser::MetaDataField::AllowComments(allow_comments) => {
+                    metadata.allow_comments.get_or_insert(*allow_comments);
+                }
+                parser::MetaDataField::AllowPings(allow_pings) => {
+                    metadata.allow_pings.get_or_insert(*allow_pings);
+                }
+                parser::MetaDataField::ConvertBreaks(convert_breaks) => {
+                    metadata.convert_breaks.get_or_insert(*convert_breaks);
+                }
+                parser::MetaDataField::PrimaryCategory(primary_category) => {
+                    metadata.primary_category.get_or_insert(*primary_category);
+                }
+                parser::MetaDataField::Category(category) => metadata.category.push(*category),
+                // Already taken from the raw entry.
+                parser::MetaDataField::Date(..) => {}
+                parser::MetaDataField::NoEntry => metadata.no_entry = true,
+                parser::MetaDataField::Tags(entry_tags) => {
+                    tags.get_or_insert(entry_tags);
+                }
+                parser::MetaDataField::Image(image) => {
+                    metadata.image.get_or_insert(*image);
+                }
+                parser::MetaDataField::UniqueUrl(unique_url) => {
+                    metadata.unique_url.get_or_insert(*unique_url);
+                }
+                parser::MetaDataField::Unknown(key, value) => {
+                    if self.keeps_unknown_fields() {
+                        metadata.extra.push((*key, *value));
+                    }
+                }
+            }
+        }
+        metadata.tags = tags.cloned().unwrap_or_default();
+
+        metadata
+    }
+
+    fn keeps_unknown_fields(&self) -> bool {
+        match self.options.unknown_fields {
+            UnknownFields::Keep => true,
+            UnknownFields::Ignore | UnknownFields::Error => false,
         }
-        Ok(MetaData {
-            author: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::Author(author) => Some(*author),
-                _ => None,
-            }),
-            title: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::Title(title) => Some(*title),
-                _ => None,
-            }),
-            basename: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::BaseName(basename) => Some(*basename),
-                _ => None,
-            }),
-            status: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::Status(status) => Some(*status),
-                _ => None,
-            }),
-            allow_comments: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::AllowComments(allow_comments) => Some(*allow_comments),
-                _ => None,
-            }),
-            allow_pings: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::AllowPings(allow_pings) => Some(*allow_pings),
-                _ => None,
-            }),
-            convert_breaks: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::ConvertBreaks(convert_breaks) => Some(*convert_breaks),
-                _ => None,
-            }),
-            primary_category: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::PrimaryCategory(primary_category) => Some(*primary_category),
-                _ => None,
-            }),
-            category: raw_metadata
-                .iter()
-                .filter_map(|m| match m {
-                    parser::MetaDataField::Category(category) => Some(*category),
-                    _ => None,
-                })
-                .collect(),
-            date: date_value.unwrap(),
-            no_entry: raw_metadata.iter().any(|m| match m {
-                parser::MetaDataField::NoEntry => true,
-                _ => false,
-            }),
-            tags: raw_metadata
-                .iter()
-                .find_map(|m| match m {
-                    parser::MetaDataField::Tags(tags) => Some(tags.clone()),
-                    _ => None,
-                })
-                .unwrap_or(vec![]),
-            image: raw_metadata.iter().find_map(|m| match m {
-                parser::MetaDataField::Image(image) => Some(*image),
-                _ => None,
-            }),
-        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{ConvertBreaks, Status};
 
     #[test]
     fn test_parse_mtif() {
@@ -328,4 +488,327 @@ mod tests {
         let entries = parser.parse(&contents).unwrap();
         insta::assert_debug_snapshot!(entries);
     }
+
+    #[test]
+    fn test_parse_mtif_with_spans() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let entries = parser.parse_with_spans(&contents).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let (entry, spans) = &entries[0];
+        assert_eq!(
+            &contents[spans.entry.range()],
+            contents.split_inclusive("--------").next().unwrap()
+        );
+        assert_eq!(spans.metadata.len(), 6);
+        assert_eq!(spans.multiline.len(), 5);
+
+        let title = spans.metadata_spans("TITLE").next().unwrap();
+        assert_eq!((title.start.line, title.start.column), (1, 1));
+        assert_eq!(&contents[title.range()], "TITLE: A dummy title");
+
+        let comments: Vec<_> = spans.multiline_spans("COMMENT").collect();
+        assert_eq!(comments.len(), entry.comments.len());
+        assert!(contents[comments[0].range()].starts_with("COMMENT:\nAUTHOR: Foo\n"));
+        assert!(contents[comments[0].range()].ends_with("\n-----"));
+
+        let (_, spans) = &entries[1];
+        let date = spans.metadata_spans("DATE").next().unwrap();
+        assert_eq!(
+            contents.lines().nth(date.start.line - 1),
+            Some(&contents[date.range()])
+        );
+    }
+
+    #[test]
+    fn test_parse_mtif_recovering() {
+        let parser = MTIFParser::new();
+        let contents = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nfirst\n-----\n--------\n\
+                        TITLE: broken\n\
+                        DATE: 13/31/2002 03:31:05\n-----\nBODY:\nsecond\n-----\n--------\n\
+                        DATE: 01/31/2002 03:31:05\n-----\nBODY:\nthird\n-----\n--------\n\
+                        \n";
+        let (entries, broken) = parser.parse_recovering(contents);
+
+        let bodies: Vec<_> = entries.iter().map(|e| e.body).collect();
+        assert_eq!(bodies, vec![Some("first"), Some("third")]);
+        assert_eq!(
+            broken,
+            vec![
+                BrokenEntry {
+                    entry: 1,
+                    lines: 7..14,
+                    error: MTIFError::InvalidDate(error::ErrorLocation {
+                        line: 8,
+                        column: 7,
+                        entry: 1,
+                        source_line: "DATE: 13/31/2002 03:31:05".to_string(),
+                    }),
+                },
+                BrokenEntry {
+                    entry: 3,
+                    lines: 20..21,
+                    error: MTIFError::TrailingInput(error::ErrorLocation {
+                        line: 20,
+                        column: 1,
+                        entry: 3,
+                        source_line: "".to_string(),
+                    }),
+                },
+            ]
+        );
+
+        let valid = std::fs::read_to_string("./example/example.txt").unwrap();
+        let (entries, broken) = parser.parse_recovering(&valid);
+        assert_eq!(entries, parser.parse(&valid).unwrap());
+        assert!(broken.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mtif_unknown_fields() {
+        let contents = "DATE: 01/31/2002 03:31:05\nCUSTOM FIELD: foo\n-----\nCUSTOM NOTES:\nbar\n-----\n--------\n";
+
+        assert!(matches!(
+            MTIFParser::new().parse(contents),
+            Err(MTIFError::UnexpectedField(_))
+        ));
+
+        let parser =
+            MTIFParser::with_options(ParseOptions::new().unknown_fields(UnknownFields::Ignore));
+        let entries = parser.parse(contents).unwrap();
+        assert!(entries[0].metadata.extra.is_empty());
+        assert!(entries[0].extra_sections.is_empty());
+
+        let parser =
+            MTIFParser::with_options(ParseOptions::new().unknown_fields(UnknownFields::Keep));
+        let entries = parser.parse(contents).unwrap();
+        assert_eq!(entries[0].metadata.extra, vec![("CUSTOM FIELD", "foo")]);
+        assert_eq!(entries[0].extra_sections, vec![("CUSTOM NOTES", "bar")]);
+    }
+
+    #[test]
+    fn test_parse_mtif_invalid_dates() {
+        let parser = MTIFParser::new();
+        for date in [
+            "00/31/2002 03:31:05",
+            "13/31/2002 03:31:05",
+            "02/30/2002 03:31:05",
+            "01/31/2002 24:31:05",
+            "01/31/2002 03:60:05",
+        ] {
+            for (contents, line) in [
+                (format!("DATE: {date}\n-----\n--------\n"), 1),
+                (
+                    format!("DATE: 01/31/2002 03:31:05\n-----\nCOMMENT:\nDATE: {date}\nfoo\n-----\n--------\n"),
+                    4,
+                ),
+                (
+                    format!("DATE: 01/31/2002 03:31:05\n-----\nPING:\nDATE: {date}\nfoo\n-----\n--------\n"),
+                    4,
+                ),
+            ] {
+                match parser.parse(&contents) {
+                    Err(MTIFError::InvalidDate(location)) => {
+                        assert_eq!(location.line, line);
+                        assert_eq!(location.column, 7);
+                        assert_eq!(location.source_line, format!("DATE: {date}"));
+                    }
+                    result => panic!("expected InvalidDate for {contents:?}, got {result:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_mtif_date_formats() {
+        let contents = "DATE: 2004-01-05T15:04:05+09:00\n-----\n\
+                        COMMENT:\nDATE: 1/5/2004 3:04:05 pm\ncomment\n-----\n\
+                        --------\n";
+        assert!(matches!(
+            MTIFParser::new().parse(contents),
+            Err(MTIFError::InvalidDate(_))
+        ));
+
+        let parser = MTIFParser::with_options(
+            ParseOptions::new()
+                .date_formats(&DateFormat::ALL)
+                .timezone(time::macros::offset!(-5)),
+        );
+        let entries = parser.parse(contents).unwrap();
+        assert_eq!(
+            entries[0].metadata.date_utc(),
+            time::macros::datetime!(2004-01-05 06:04:05 UTC)
+        );
+        assert_eq!(
+            entries[0].comments[0].date_utc(),
+            Some(time::macros::datetime!(2004-01-05 20:04:05 UTC))
+        );
+    }
+
+    #[test]
+    fn test_parse_mtif_dialects() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let parser = MTIFParser::with_options(ParseOptions::new().dialect(Dialect::MovableType));
+        assert_eq!(parser.parse(&contents).unwrap().len(), 2);
+
+        let contents = std::fs::read_to_string("./example/hatena.txt").unwrap();
+        assert!(matches!(
+            MTIFParser::new().parse(&contents),
+            Err(MTIFError::UnexpectedField(_))
+        ));
+
+        let parser = MTIFParser::with_options(ParseOptions::new().dialect(Dialect::Hatena));
+        let entries = parser.parse(&contents).unwrap();
+        let statuses: Vec<_> = entries.iter().map(|e| e.metadata.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(Status::Publish),
+                Some(Status::Future),
+                Some(Status::Draft)
+            ]
+        );
+        let convert_breaks: Vec<_> = entries.iter().map(|e| e.metadata.convert_breaks).collect();
+        assert_eq!(
+            convert_breaks,
+            vec![
+                Some(ConvertBreaks::None),
+                Some(ConvertBreaks::HatenaNotation),
+                Some(ConvertBreaks::Markdown)
+            ]
+        );
+        let metadata = &entries[0].metadata;
+        assert_eq!(metadata.basename, Some("2019/04/01/120000"));
+        assert_eq!(metadata.category, vec!["日記", "お知らせ"]);
+        assert_eq!(
+            metadata.image,
+            Some("https://cdn-ak.f.st-hatena.com/images/fotolife/h/hatenablog/20190401/20190401120000.jpg")
+        );
+        assert_eq!(metadata.date, time::macros::datetime!(2019-04-01 12:00:00));
+        assert_eq!(entries[0].comments[0].author, Some("id:hatena"));
+
+        // CONVERT BREAKS values do not depend on the dialect.
+        let contents = contents.replace("STATUS: Scheduled", "STATUS: Future");
+        let entries = MTIFParser::new().parse(&contents).unwrap();
+        assert_eq!(
+            entries[1].metadata.convert_breaks,
+            Some(ConvertBreaks::HatenaNotation)
+        );
+    }
+
+    #[test]
+    fn test_parse_mtif_duplicate_fields() {
+        let contents = "TITLE: First\n\
+                        CATEGORY: News\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        TITLE: Second\n\
+                        CATEGORY: Media\n\
+                        TAGS: a,b\n\
+                        DATE: 02/01/2002 03:31:05 PM\n\
+                        TAGS: c\n\
+                        -----\n\
+                        BODY:\n\
+                        first body\n\
+                        -----\n\
+                        BODY:\n\
+                        second body\n\
+                        -----\n\
+                        PINGED URLS:\n\
+                        http://example.com/1\n\
+                        -----\n\
+                        PINGED URLS:\n\
+                        http://example.com/2\n\
+                        -----\n\
+                        --------\n";
+        let entries = MTIFParser::new().parse(contents).unwrap();
+        let entry = &entries[0];
+
+        assert_eq!(entry.metadata.title, Some("First"));
+        assert_eq!(
+            entry.metadata.date,
+            time::macros::datetime!(2002-01-31 15:31:05)
+        );
+        assert_eq!(entry.metadata.tags, vec!["a", "b"]);
+        assert_eq!(entry.metadata.category, vec!["News", "Media"]);
+        assert_eq!(entry.body, Some("first body"));
+        assert_eq!(
+            entry.pinged_urls,
+            vec!["http://example.com/1", "http://example.com/2"]
+        );
+    }
+
+    #[test]
+    fn test_parse_mtif_line_endings() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let expected = parser.parse(&contents).unwrap();
+        let normalize = |text: &str, line_ending: &str| text.replace(line_ending, "\n");
+
+        for line_ending in ["\r\n", "\r"] {
+            let converted = contents.replace('\n', line_ending);
+            let entries = parser.parse(&converted).unwrap();
+            assert_eq!(entries.len(), expected.len());
+
+            for (entry, expected) in entries.iter().zip(&expected) {
+                assert_eq!(entry.metadata, expected.metadata);
+                assert_eq!(
+                    entry
+                        .body
+                        .map(|text| normalize(text, line_ending))
+                        .as_deref(),
+                    expected.body
+                );
+                assert_eq!(
+                    entry
+                        .excerpt
+                        .map(|text| normalize(text, line_ending))
+                        .as_deref(),
+                    expected.excerpt
+                );
+                for (comment, expected) in entry.comments.iter().zip(&expected.comments) {
+                    assert_eq!(normalize(comment.text, line_ending), expected.text);
+                    assert_eq!(
+                        Comment {
+                            text: expected.text,
+                            ..comment.clone()
+                        },
+                        *expected
+                    );
+                }
+                for (ping, expected) in entry.pings.iter().zip(&expected.pings) {
+                    assert_eq!(normalize(ping.text, line_ending), expected.text);
+                    assert_eq!(
+                        Ping {
+                            text: expected.text,
+                            ..ping.clone()
+                        },
+                        *expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parse_parallel() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        // Large enough to be split into several pieces, with `--------` body
+        // lines that must not be taken as entry ends.
+        let contents =
+            vec![contents.replace("Another paragraph", "--------\nAnother"); 500].join("\n");
+        assert_eq!(
+            parser.parse_parallel(&contents).unwrap(),
+            parser.parse(&contents).unwrap()
+        );
+
+        let broken = format!("{contents}\n{}", contents.replacen("DATE: ", "DATE: x", 1));
+        assert!(parser.parse_parallel(&broken).is_err());
+        assert_eq!(
+            parser.parse_parallel(&broken).err(),
+            parser.parse(&broken).err()
+        );
+    }
 }
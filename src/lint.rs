@@ -0,0 +1,343 @@
+//! Semantic checks on parsed entries.
+//!
+//! The parser only checks syntax. [`Linter`] looks for data that parses
+//! fine but usually breaks an import, such as two entries sharing a
+//! `BASENAME`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::model::Status;
+use crate::MTIFEntry;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Rule {
+    /// Two or more entries have the same `BASENAME`.
+    DuplicateBasename,
+    /// `PRIMARY CATEGORY` is not also listed as a `CATEGORY`.
+    PrimaryCategoryNotInCategory,
+    /// `STATUS: Future` with a date that has already passed.
+    FutureStatusInPast,
+    /// `BODY` is missing or blank.
+    EmptyBody,
+    /// A comment is dated before its entry.
+    CommentBeforeEntry,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::DuplicateBasename,
+        Rule::PrimaryCategoryNotInCategory,
+        Rule::FutureStatusInPast,
+        Rule::EmptyBody,
+        Rule::CommentBeforeEntry,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::DuplicateBasename => "duplicate-basename",
+            Rule::PrimaryCategoryNotInCategory => "primary-category-not-in-category",
+            Rule::FutureStatusInPast => "future-status-in-past",
+            Rule::EmptyBody => "empty-body",
+            Rule::CommentBeforeEntry => "comment-before-entry",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Rule::DuplicateBasename => Severity::Error,
+            Rule::PrimaryCategoryNotInCategory
+            | Rule::FutureStatusInPast
+            | Rule::EmptyBody
+            | Rule::CommentBeforeEntry => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub rule: Rule,
+    /// 0-based index of the offending entry.
+    pub entry: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(rule: Rule, entry: usize, message: String) -> Self {
+        Diagnostic {
+            severity: rule.severity(),
+            rule,
+            entry,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{} [{}] entry {}: {}",
+            severity,
+            self.rule.id(),
+            self.entry,
+            self.message
+        )
+    }
+}
+
+/// Runs the enabled [`Rule`]s over a list of entries. All rules are
+/// enabled by default.
+#[derive(Debug, Clone)]
+pub struct Linter {
+    disabled: Vec<Rule>,
+    now: Option<time::OffsetDateTime>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Linter {
+            disabled: vec![],
+            now: None,
+        }
+    }
+
+    pub fn enable(mut self, rule: Rule) -> Self {
+        self.disabled.retain(|disabled| *disabled != rule);
+        self
+    }
+
+    pub fn disable(mut self, rule: Rule) -> Self {
+        if !self.disabled.contains(&rule) {
+            self.disabled.push(rule);
+        }
+        self
+    }
+
+    /// The time `FutureStatusInPast` compares entry dates against.
+    /// Defaults to the current time.
+    pub fn now(mut self, now: time::OffsetDateTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn is_enabled(&self, rule: Rule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    /// Diagnostics are ordered by entry, then by rule.
+    pub fn lint(&self, entries: &[MTIFEntry<'_>]) -> Vec<Diagnostic> {
+        let now = self.now.unwrap_or_else(time::OffsetDateTime::now_utc);
+        let mut basenames: HashMap<&str, usize> = HashMap::new();
+        let mut diagnostics = vec![];
+
+        for (index, entry) in entries.iter().enumerate() {
+            let metadata = &entry.metadata;
+
+            if let Some(basename) = metadata.basename {
+                let first = *basenames.entry(basename).or_insert(index);
+                if first != index && self.is_enabled(Rule::DuplicateBasename) {
+                    diagnostics.push(Diagnostic::new(
+                        Rule::DuplicateBasename,
+                        index,
+                        format!("BASENAME {:?} is already used by entry {}", basename, first),
+                    ));
+                }
+            }
+
+            if let Some(primary_category) = metadata.primary_category {
+                if !metadata.category.contains(&primary_category)
+                    && self.is_enabled(Rule::PrimaryCategoryNotInCategory)
+                {
+                    diagnostics.push(Diagnostic::new(
+                        Rule::PrimaryCategoryNotInCategory,
+                        index,
+                        format!(
+                            "PRIMARY CATEGORY {:?} is not listed in CATEGORY",
+                            primary_category
+                        ),
+                    ));
+                }
+            }
+
+            if metadata.status == Some(Status::Future)
+                && metadata.date_utc() < now
+                && self.is_enabled(Rule::FutureStatusInPast)
+            {
+                diagnostics.push(Diagnostic::new(
+                    Rule::FutureStatusInPast,
+                    index,
+                    "STATUS is Future but DATE has already passed".to_string(),
+                ));
+            }
+
+            if entry.body.is_none_or(|body| body.trim().is_empty())
+                && self.is_enabled(Rule::EmptyBody)
+            {
+                diagnostics.push(Diagnostic::new(
+                    Rule::EmptyBody,
+                    index,
+                    "BODY is empty".to_string(),
+                ));
+            }
+
+            if self.is_enabled(Rule::CommentBeforeEntry) {
+                for (comment_index, comment) in entry.comments.iter().enumerate() {
+                    if comment
+                        .date_utc()
+                        .is_some_and(|date| date < metadata.date_utc())
+                    {
+                        diagnostics.push(Diagnostic::new(
+                            Rule::CommentBeforeEntry,
+                            index,
+                            format!("comment {} is dated before the entry", comment_index),
+                        ));
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MTIFParser;
+
+    const CONTENTS: &str = "BASENAME: foo\n\
+                            STATUS: Future\n\
+                            PRIMARY CATEGORY: News\n\
+                            CATEGORY: Media\n\
+                            DATE: 01/31/2002 03:31:05 PM\n\
+                            -----\n\
+                            BODY:\n\
+                            \n\
+                            -----\n\
+                            COMMENT:\n\
+                            DATE: 01/30/2002 03:31:05 PM\n\
+                            Too early\n\
+                            -----\n\
+                            --------\n\
+                            BASENAME: foo\n\
+                            PRIMARY CATEGORY: News\n\
+                            CATEGORY: News\n\
+                            DATE: 01/31/2002 03:31:05 PM\n\
+                            -----\n\
+                            BODY:\n\
+                            Body\n\
+                            -----\n\
+                            COMMENT:\n\
+                            DATE: 01/31/2002 03:31:05 PM\n\
+                            Just in time\n\
+                            -----\n\
+                            --------\n";
+
+    #[test]
+    fn test_lint() {
+        let entries = MTIFParser::new().parse(CONTENTS).unwrap();
+        let diagnostics = Linter::new()
+            .now(time::macros::datetime!(2024-01-01 00:00:00 UTC))
+            .lint(&entries);
+
+        let found: Vec<_> = diagnostics.iter().map(|d| (d.entry, d.rule)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (0, Rule::PrimaryCategoryNotInCategory),
+                (0, Rule::FutureStatusInPast),
+                (0, Rule::EmptyBody),
+                (0, Rule::CommentBeforeEntry),
+                (1, Rule::DuplicateBasename),
+            ]
+        );
+        assert_eq!(
+            diagnostics[4].to_string(),
+            "error [duplicate-basename] entry 1: BASENAME \"foo\" is already used by entry 0"
+        );
+    }
+
+    #[test]
+    fn test_lint_comment_before_entry_offsets() {
+        let contents = "DATE: 2004-01-05T15:00:00+09:00\n\
+                        -----\n\
+                        BODY:\n\
+                        Body\n\
+                        -----\n\
+                        COMMENT:\n\
+                        DATE: 2004-01-05T10:00:00Z\n\
+                        After the entry\n\
+                        -----\n\
+                        COMMENT:\n\
+                        DATE: 2004-01-05T05:00:00Z\n\
+                        Before the entry\n\
+                        -----\n\
+                        --------\n";
+        let parser = MTIFParser::with_options(
+            crate::ParseOptions::new().date_formats(&[crate::DateFormat::Iso8601]),
+        );
+        let entries = parser.parse(contents).unwrap();
+        let diagnostics = Linter::new()
+            .now(time::macros::datetime!(2024-01-01 00:00:00 UTC))
+            .lint(&entries);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, Rule::CommentBeforeEntry);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning [comment-before-entry] entry 0: comment 1 is dated before the entry"
+        );
+    }
+
+    #[test]
+    fn test_lint_future_status_offsets() {
+        let entries = MTIFParser::new().parse(CONTENTS).unwrap();
+        let future_in_past = |now| {
+            Linter::new()
+                .now(now)
+                .lint(&entries)
+                .iter()
+                .any(|d| d.rule == Rule::FutureStatusInPast)
+        };
+
+        // The entry is dated 15:31:05 UTC.
+        assert!(!future_in_past(
+            time::macros::datetime!(2002-01-31 16:00:00 +01:00)
+        ));
+        assert!(future_in_past(
+            time::macros::datetime!(2002-01-31 16:00:00 -01:00)
+        ));
+    }
+
+    #[test]
+    fn test_lint_disabled_rules() {
+        let entries = MTIFParser::new().parse(CONTENTS).unwrap();
+        let mut linter = Linter::new().now(time::macros::datetime!(2000-01-01 00:00:00 UTC));
+        for rule in Rule::ALL {
+            linter = linter.disable(rule);
+        }
+        assert!(linter.lint(&entries).is_empty());
+
+        let diagnostics = linter.enable(Rule::EmptyBody).lint(&entries);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, Rule::EmptyBody);
+    }
+}
@@ -1,21 +1,85 @@
+// ISO-8601 dates without offset, as written in the input; the offset they
+// are in is serialized separately.
+#[cfg(feature = "serde")]
+mod iso8601 {
+    time::serde::format_description!(
+        pub(super) date_time,
+        PrimitiveDateTime,
+        "[year]-[month]-[day]T[hour]:[minute]:[second]"
+    );
+    time::serde::format_description!(
+        pub(super) offset,
+        UtcOffset,
+        "[offset_hour sign:mandatory]:[offset_minute]"
+    );
+
+    pub(super) fn utc() -> time::UtcOffset {
+        time::UtcOffset::UTC
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Status {
     Draft,
     Publish,
     Future,
 }
 
+/// The moderation status of a comment or ping.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CommentStatus {
+    Approved,
+    Pending,
+    Spam,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum ConvertBreaks {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConvertBreaks<'a> {
     None,
     Convert,
     Markdown,
     MarkdownWithSmartypants,
+    #[cfg_attr(feature = "serde", serde(rename = "richtext"))]
     RichText,
+    #[cfg_attr(feature = "serde", serde(rename = "textile_2"))]
     Textile2,
+    /// `__default__`: the blog's default format.
+    #[cfg_attr(feature = "serde", serde(rename = "__default__"))]
+    Default,
+    /// Hatena notation.
+    #[cfg_attr(feature = "serde", serde(rename = "hatena"))]
+    HatenaNotation,
+    Wysiwyg,
+    /// Any other text filter, such as one added by a plugin, as written.
+    Other(&'a str),
+}
+
+impl<'a> ConvertBreaks<'a> {
+    /// The value as written after `CONVERT BREAKS:`.
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            ConvertBreaks::None => "0",
+            ConvertBreaks::Convert => "1",
+            ConvertBreaks::Markdown => "markdown",
+            ConvertBreaks::MarkdownWithSmartypants => "markdown_with_smartypants",
+            ConvertBreaks::RichText => "richtext",
+            ConvertBreaks::Textile2 => "textile_2",
+            ConvertBreaks::Default => "__default__",
+            ConvertBreaks::HatenaNotation => "hatena",
+            ConvertBreaks::Wysiwyg => "wysiwyg",
+            ConvertBreaks::Other(value) => value,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MetaData<'a> {
     pub author: Option<&'a str>,
     pub title: Option<&'a str>,
@@ -23,36 +87,66 @@ pub struct MetaData<'a> {
     pub status: Option<Status>,
     pub allow_comments: Option<bool>,
     pub allow_pings: Option<bool>,
-    pub convert_breaks: Option<ConvertBreaks>,
+    pub convert_breaks: Option<ConvertBreaks<'a>>,
     pub primary_category: Option<&'a str>,
     pub category: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time"))]
     pub date: time::PrimitiveDateTime,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
     pub no_entry: bool,
     pub tags: Vec<&'a str>,
     pub image: Option<&'a str>,
+    pub unique_url: Option<&'a str>,
+    pub extra: Vec<(&'a str, &'a str)>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Comment<'a> {
     pub author: Option<&'a str>,
     pub email: Option<&'a str>,
     pub url: Option<&'a str>,
     pub ip: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time::option"))]
     pub date: Option<time::PrimitiveDateTime>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
+    pub status: Option<CommentStatus>,
     pub text: &'a str,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Ping<'a> {
     pub title: Option<&'a str>,
     pub url: Option<&'a str>,
     pub ip: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time::option"))]
     pub date: Option<time::PrimitiveDateTime>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
     pub blog_name: Option<&'a str>,
+    pub status: Option<CommentStatus>,
     pub text: &'a str,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// An entry of an export.
+///
+/// Of a single-valued field given more than once, the first is kept.
+/// Repeatable fields (`CATEGORY`, `COMMENT`, `PING`, `PINGED URLS` and
+/// unknown fields) keep every occurrence in input order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MTIFEntry<'a> {
     pub metadata: MetaData<'a>,
     pub body: Option<&'a str>,
@@ -61,4 +155,400 @@ pub struct MTIFEntry<'a> {
     pub keywords: Option<&'a str>,
     pub comments: Vec<Comment<'a>>,
     pub pings: Vec<Ping<'a>>,
+    pub pinged_urls: Vec<&'a str>,
+    pub extra_sections: Vec<(&'a str, &'a str)>,
+}
+
+impl MetaData<'_> {
+    /// `date` in the offset given by `utc_offset`.
+    pub fn date_with_offset(&self) -> time::OffsetDateTime {
+        self.date.assume_offset(self.utc_offset)
+    }
+
+    pub fn date_utc(&self) -> time::OffsetDateTime {
+        self.date_with_offset().to_offset(time::UtcOffset::UTC)
+    }
+}
+
+impl Comment<'_> {
+    /// `date` in the offset given by `utc_offset`.
+    pub fn date_with_offset(&self) -> Option<time::OffsetDateTime> {
+        self.date.map(|date| date.assume_offset(self.utc_offset))
+    }
+
+    pub fn date_utc(&self) -> Option<time::OffsetDateTime> {
+        self.date_with_offset()
+            .map(|date| date.to_offset(time::UtcOffset::UTC))
+    }
+}
+
+impl Ping<'_> {
+    /// `date` in the offset given by `utc_offset`.
+    pub fn date_with_offset(&self) -> Option<time::OffsetDateTime> {
+        self.date.map(|date| date.assume_offset(self.utc_offset))
+    }
+
+    pub fn date_utc(&self) -> Option<time::OffsetDateTime> {
+        self.date_with_offset()
+            .map(|date| date.to_offset(time::UtcOffset::UTC))
+    }
+}
+
+/*
+ * Owned counterparts of the borrowed model, for entries that outlive the
+ * parsed input or are built programmatically. Only these implement
+ * `Deserialize`: serialized text is usually escaped, so it cannot be
+ * borrowed.
+ */
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OwnedConvertBreaks {
+    None,
+    Convert,
+    Markdown,
+    MarkdownWithSmartypants,
+    #[cfg_attr(feature = "serde", serde(rename = "richtext"))]
+    RichText,
+    #[cfg_attr(feature = "serde", serde(rename = "textile_2"))]
+    Textile2,
+    #[cfg_attr(feature = "serde", serde(rename = "__default__"))]
+    Default,
+    #[cfg_attr(feature = "serde", serde(rename = "hatena"))]
+    HatenaNotation,
+    Wysiwyg,
+    Other(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedMetaData {
+    pub author: Option<String>,
+    pub title: Option<String>,
+    pub basename: Option<String>,
+    pub status: Option<Status>,
+    pub allow_comments: Option<bool>,
+    pub allow_pings: Option<bool>,
+    pub convert_breaks: Option<OwnedConvertBreaks>,
+    pub primary_category: Option<String>,
+    pub category: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time"))]
+    pub date: time::PrimitiveDateTime,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
+    pub no_entry: bool,
+    pub tags: Vec<String>,
+    pub image: Option<String>,
+    pub unique_url: Option<String>,
+    pub extra: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedComment {
+    pub author: Option<String>,
+    pub email: Option<String>,
+    pub url: Option<String>,
+    pub ip: Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time::option"))]
+    pub date: Option<time::PrimitiveDateTime>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
+    pub status: Option<CommentStatus>,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedPing {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub ip: Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "iso8601::date_time::option"))]
+    pub date: Option<time::PrimitiveDateTime>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "iso8601::offset", default = "iso8601::utc")
+    )]
+    pub utc_offset: time::UtcOffset,
+    pub blog_name: Option<String>,
+    pub status: Option<CommentStatus>,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedMTIFEntry {
+    pub metadata: OwnedMetaData,
+    pub body: Option<String>,
+    pub extended_body: Option<String>,
+    pub excerpt: Option<String>,
+    pub keywords: Option<String>,
+    pub comments: Vec<OwnedComment>,
+    pub pings: Vec<OwnedPing>,
+    pub pinged_urls: Vec<String>,
+    pub extra_sections: Vec<(String, String)>,
+}
+
+fn to_owned_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+fn as_borrowed_strings(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
+fn to_owned_pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn as_borrowed_pairs(pairs: &[(String, String)]) -> Vec<(&str, &str)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect()
+}
+
+impl ConvertBreaks<'_> {
+    pub fn into_owned(self) -> OwnedConvertBreaks {
+        match self {
+            ConvertBreaks::None => OwnedConvertBreaks::None,
+            ConvertBreaks::Convert => OwnedConvertBreaks::Convert,
+            ConvertBreaks::Markdown => OwnedConvertBreaks::Markdown,
+            ConvertBreaks::MarkdownWithSmartypants => OwnedConvertBreaks::MarkdownWithSmartypants,
+            ConvertBreaks::RichText => OwnedConvertBreaks::RichText,
+            ConvertBreaks::Textile2 => OwnedConvertBreaks::Textile2,
+            ConvertBreaks::Default => OwnedConvertBreaks::Default,
+            ConvertBreaks::HatenaNotation => OwnedConvertBreaks::HatenaNotation,
+            ConvertBreaks::Wysiwyg => OwnedConvertBreaks::Wysiwyg,
+            ConvertBreaks::Other(value) => OwnedConvertBreaks::Other(value.to_string()),
+        }
+    }
+}
+
+impl OwnedConvertBreaks {
+    pub fn as_borrowed(&self) -> ConvertBreaks<'_> {
+        match self {
+            OwnedConvertBreaks::None => ConvertBreaks::None,
+            OwnedConvertBreaks::Convert => ConvertBreaks::Convert,
+            OwnedConvertBreaks::Markdown => ConvertBreaks::Markdown,
+            OwnedConvertBreaks::MarkdownWithSmartypants => ConvertBreaks::MarkdownWithSmartypants,
+            OwnedConvertBreaks::RichText => ConvertBreaks::RichText,
+            OwnedConvertBreaks::Textile2 => ConvertBreaks::Textile2,
+            OwnedConvertBreaks::Default => ConvertBreaks::Default,
+            OwnedConvertBreaks::HatenaNotation => ConvertBreaks::HatenaNotation,
+            OwnedConvertBreaks::Wysiwyg => ConvertBreaks::Wysiwyg,
+            OwnedConvertBreaks::Other(value) => ConvertBreaks::Other(value),
+        }
+    }
+}
+
+impl MetaData<'_> {
+    pub fn into_owned(self) -> OwnedMetaData {
+        OwnedMetaData {
+            author: self.author.map(str::to_string),
+            title: self.title.map(str::to_string),
+            basename: self.basename.map(str::to_string),
+            status: self.status,
+            allow_comments: self.allow_comments,
+            allow_pings: self.allow_pings,
+            convert_breaks: self.convert_breaks.map(ConvertBreaks::into_owned),
+            primary_category: self.primary_category.map(str::to_string),
+            category: to_owned_strings(&self.category),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            no_entry: self.no_entry,
+            tags: to_owned_strings(&self.tags),
+            image: self.image.map(str::to_string),
+            unique_url: self.unique_url.map(str::to_string),
+            extra: to_owned_pairs(&self.extra),
+        }
+    }
+}
+
+impl OwnedMetaData {
+    pub fn as_borrowed(&self) -> MetaData<'_> {
+        MetaData {
+            author: self.author.as_deref(),
+            title: self.title.as_deref(),
+            basename: self.basename.as_deref(),
+            status: self.status,
+            allow_comments: self.allow_comments,
+            allow_pings: self.allow_pings,
+            convert_breaks: self
+                .convert_breaks
+                .as_ref()
+                .map(OwnedConvertBreaks::as_borrowed),
+            primary_category: self.primary_category.as_deref(),
+            category: as_borrowed_strings(&self.category),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            no_entry: self.no_entry,
+            tags: as_borrowed_strings(&self.tags),
+            image: self.image.as_deref(),
+            unique_url: self.unique_url.as_deref(),
+            extra: as_borrowed_pairs(&self.extra),
+        }
+    }
+}
+
+impl Comment<'_> {
+    pub fn into_owned(self) -> OwnedComment {
+        OwnedComment {
+            author: self.author.map(str::to_string),
+            email: self.email.map(str::to_string),
+            url: self.url.map(str::to_string),
+            ip: self.ip.map(str::to_string),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            status: self.status,
+            text: self.text.to_string(),
+        }
+    }
+}
+
+impl OwnedComment {
+    pub fn as_borrowed(&self) -> Comment<'_> {
+        Comment {
+            author: self.author.as_deref(),
+            email: self.email.as_deref(),
+            url: self.url.as_deref(),
+            ip: self.ip.as_deref(),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            status: self.status,
+            text: &self.text,
+        }
+    }
+}
+
+impl Ping<'_> {
+    pub fn into_owned(self) -> OwnedPing {
+        OwnedPing {
+            title: self.title.map(str::to_string),
+            url: self.url.map(str::to_string),
+            ip: self.ip.map(str::to_string),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            blog_name: self.blog_name.map(str::to_string),
+            status: self.status,
+            text: self.text.to_string(),
+        }
+    }
+}
+
+impl OwnedPing {
+    pub fn as_borrowed(&self) -> Ping<'_> {
+        Ping {
+            title: self.title.as_deref(),
+            url: self.url.as_deref(),
+            ip: self.ip.as_deref(),
+            date: self.date,
+            utc_offset: self.utc_offset,
+            blog_name: self.blog_name.as_deref(),
+            status: self.status,
+            text: &self.text,
+        }
+    }
+}
+
+impl MTIFEntry<'_> {
+    pub fn into_owned(self) -> OwnedMTIFEntry {
+        OwnedMTIFEntry {
+            metadata: self.metadata.into_owned(),
+            body: self.body.map(str::to_string),
+            extended_body: self.extended_body.map(str::to_string),
+            excerpt: self.excerpt.map(str::to_string),
+            keywords: self.keywords.map(str::to_string),
+            comments: self.comments.into_iter().map(Comment::into_owned).collect(),
+            pings: self.pings.into_iter().map(Ping::into_owned).collect(),
+            pinged_urls: to_owned_strings(&self.pinged_urls),
+            extra_sections: to_owned_pairs(&self.extra_sections),
+        }
+    }
+}
+
+impl OwnedMTIFEntry {
+    pub fn as_borrowed(&self) -> MTIFEntry<'_> {
+        MTIFEntry {
+            metadata: self.metadata.as_borrowed(),
+            body: self.body.as_deref(),
+            extended_body: self.extended_body.as_deref(),
+            excerpt: self.excerpt.as_deref(),
+            keywords: self.keywords.as_deref(),
+            comments: self
+                .comments
+                .iter()
+                .map(OwnedComment::as_borrowed)
+                .collect(),
+            pings: self.pings.iter().map(OwnedPing::as_borrowed).collect(),
+            pinged_urls: as_borrowed_strings(&self.pinged_urls),
+            extra_sections: as_borrowed_pairs(&self.extra_sections),
+        }
+    }
+}
+
+impl<'a> From<MTIFEntry<'a>> for OwnedMTIFEntry {
+    fn from(entry: MTIFEntry<'a>) -> Self {
+        entry.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MTIFParser;
+
+    #[test]
+    fn test_owned_round_trip() {
+        let parser = MTIFParser::new();
+        let owned: Vec<_> = {
+            let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+            let entries = parser.parse(&contents).unwrap();
+            entries.into_iter().map(|e| e.into_owned()).collect()
+        };
+
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let entries = parser.parse(&contents).unwrap();
+        let borrowed: Vec<_> = owned.iter().map(|e| e.as_borrowed()).collect();
+        assert_eq!(borrowed, entries);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = MTIFParser::new().parse(&contents).unwrap();
+        let mut entry = entries.remove(0);
+        entry.metadata.status = Some(super::Status::Draft);
+        entry.metadata.convert_breaks = Some(super::ConvertBreaks::MarkdownWithSmartypants);
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["metadata"]["status"], "draft");
+        assert_eq!(
+            json["metadata"]["convert_breaks"],
+            "markdown_with_smartypants"
+        );
+        assert_eq!(json["metadata"]["date"], "2002-01-31T15:31:05");
+        assert_eq!(json["comments"][1]["date"], "2002-02-01T04:02:07");
+        assert_eq!(json["pings"][0]["title"], "My Entry");
+
+        let owned: super::OwnedMTIFEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(owned.as_borrowed(), entry);
+
+        // The body has line breaks, which are escaped in the JSON text.
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r"This is the body.\n"));
+        let owned: super::OwnedMTIFEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(owned.as_borrowed(), entry);
+    }
 }
@@ -0,0 +1,111 @@
+/// What to do with fields the parser does not know.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum UnknownFields {
+    /// Keep them in `MetaData::extra` and `MTIFEntry::extra_sections`.
+    Keep,
+    /// Skip them.
+    Ignore,
+    /// Fail with `MTIFError::UnexpectedField`.
+    #[default]
+    Error,
+}
+
+/// The exporter that wrote the input. Each dialect accepts the `STATUS`
+/// values that exporter writes on top of Movable Type's. `CONVERT BREAKS`
+/// values are read the same way in every dialect.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Dialect {
+    /// `STATUS: Draft|Publish|Future`, as in `example/example.txt`.
+    #[default]
+    MovableType,
+    /// Hatena Blog, as in `example/hatena.txt`: also `STATUS: Scheduled`,
+    /// read as `Status::Future`. Bodies are exported as HTML whatever the
+    /// editing mode.
+    Hatena,
+}
+
+/// A way of writing `DATE:` values.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DateFormat {
+    /// `MM/DD/YYYY hh:MM:SS [AM|PM]`, as written by Movable Type.
+    MovableType,
+    /// `M/D/YYYY h:MM:SS [am|pm]`: one- or two-digit month, day and hour,
+    /// and AM/PM in any case.
+    Relaxed,
+    /// `YYYY-MM-DDThh:MM:SS`, optionally followed by `Z` or `±hh:MM`. A
+    /// space may replace the `T`.
+    Iso8601,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 3] = [
+        DateFormat::MovableType,
+        DateFormat::Relaxed,
+        DateFormat::Iso8601,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A set of [`DateFormat`]s.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct DateFormats(u8);
+
+impl DateFormats {
+    pub(crate) fn contains(self, format: DateFormat) -> bool {
+        self.0 & format.bit() != 0
+    }
+}
+
+impl Default for DateFormats {
+    fn default() -> Self {
+        DateFormats(DateFormat::MovableType.bit())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ParseOptions {
+    pub(crate) unknown_fields: UnknownFields,
+    pub(crate) timezone: Option<time::UtcOffset>,
+    pub(crate) date_formats: DateFormats,
+    pub(crate) dialect: Dialect,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn unknown_fields(mut self, unknown_fields: UnknownFields) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
+
+    /// The offset dates in the input are in, usually the blog's timezone.
+    /// Defaults to UTC.
+    pub fn timezone(mut self, offset: time::UtcOffset) -> Self {
+        self.timezone = Some(offset);
+        self
+    }
+
+    /// The formats accepted for dates, tried in the order of
+    /// [`DateFormat::ALL`]. Defaults to [`DateFormat::MovableType`] only.
+    /// An offset written in an ISO-8601 date takes precedence over
+    /// [`timezone`](Self::timezone).
+    pub fn date_formats(mut self, formats: &[DateFormat]) -> Self {
+        self.date_formats = DateFormats(formats.iter().fold(0, |bits, f| bits | f.bit()));
+        self
+    }
+
+    /// Defaults to [`Dialect::MovableType`].
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    pub(crate) fn utc_offset(&self) -> time::UtcOffset {
+        self.timezone.unwrap_or(time::UtcOffset::UTC)
+    }
+}
@@ -17,24 +17,76 @@ mod image;
 mod keywords;
 mod no_entry;
 mod ping;
+mod pinged_urls;
 mod primary_category;
 mod status;
 mod tags;
 mod title;
+mod unique_url;
+mod unknown;
 mod utils;
 
-use crate::model::{ConvertBreaks, Status};
+use std::cell::OnceCell;
+
+use crate::error::{ErrorLocation, MTIFError};
+use crate::model::{CommentStatus, ConvertBreaks, Status};
+use crate::options::{ParseOptions, UnknownFields};
+use crate::span::LineIndex;
 
 use nom::{
     branch,
     bytes::{self},
-    character::complete::newline,
-    combinator::{eof, opt},
+    combinator,
+    error::ErrorKind,
     multi::{self},
-    sequence::{self, terminated},
-    IResult,
+    sequence::{self},
 };
 
+pub type IResult<'a, O> = nom::IResult<&'a str, O, ParseError<'a>>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseErrorKind {
+    Nom(ErrorKind),
+    InvalidDate,
+    MissingDate,
+    MissingSeparator,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError<'a> {
+    pub input: &'a str,
+    pub kind: ParseErrorKind,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(input: &'a str, kind: ParseErrorKind) -> Self {
+        ParseError { input, kind }
+    }
+
+    fn into_mtif_error(self, locator: &ErrorLocator<'_>, entry: usize) -> MTIFError {
+        let location = locator.locate(self.input, entry);
+        match self.kind {
+            ParseErrorKind::InvalidDate => MTIFError::InvalidDate(location),
+            ParseErrorKind::MissingDate => MTIFError::MissingDate(location),
+            ParseErrorKind::MissingSeparator => MTIFError::MissingSeparator(location),
+            ParseErrorKind::Nom(_) if self.input.is_empty() => {
+                MTIFError::MissingSeparator(location)
+            }
+            ParseErrorKind::Nom(_) => MTIFError::UnexpectedField(location),
+        }
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for ParseError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        ParseError::new(input, ParseErrorKind::Nom(kind))
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum MetaDataField<'a> {
     Author(&'a str),
@@ -43,13 +95,15 @@ pub enum MetaDataField<'a> {
     Status(Status),
     AllowComments(bool),
     AllowPings(bool),
-    ConvertBreaks(ConvertBreaks),
+    ConvertBreaks(ConvertBreaks<'a>),
     Category(&'a str),
     PrimaryCategory(&'a str),
     Tags(Vec<&'a str>),
-    Date(time::PrimitiveDateTime),
+    Date(time::PrimitiveDateTime, Option<time::UtcOffset>),
     Image(&'a str),
+    UniqueUrl(&'a str),
     NoEntry,
+    Unknown(&'a str, &'a str),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -64,6 +118,8 @@ pub enum MultiLineField<'a> {
         url: Option<&'a str>,
         ip: Option<&'a str>,
         date: Option<time::PrimitiveDateTime>,
+        utc_offset: Option<time::UtcOffset>,
+        status: Option<CommentStatus>,
         text: &'a str,
     },
     Ping {
@@ -71,75 +127,326 @@ pub enum MultiLineField<'a> {
         url: Option<&'a str>,
         ip: Option<&'a str>,
         date: Option<time::PrimitiveDateTime>,
+        utc_offset: Option<time::UtcOffset>,
         blog_name: Option<&'a str>,
+        status: Option<CommentStatus>,
         text: &'a str,
     },
+    PingedUrls(Vec<&'a str>),
+    Unknown(&'a str, &'a str),
 }
 
+// The `*_sources` fields hold the slice of the input each field was parsed
+// from, in the same order as the fields themselves.
 #[derive(Debug, PartialEq, Eq)]
 pub struct RawMTIFEntry<'a> {
+    pub source: &'a str,
+    // The first `DATE` field, which every entry has.
+    pub date: utils::DateValue,
     pub metadata: Vec<MetaDataField<'a>>,
+    pub metadata_sources: Vec<&'a str>,
     pub multiline_data: Vec<MultiLineField<'a>>,
+    pub multiline_sources: Vec<&'a str>,
+}
+
+// Parsers for unknown fields only apply when the options do not reject them.
+fn unless_rejected<'a, O>(
+    options: &ParseOptions,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+    let enabled = options.unknown_fields != UnknownFields::Error;
+    move |input| {
+        if enabled {
+            parser(input)
+        } else {
+            Err(nom::Err::Error(ParseError::new(
+                input,
+                ParseErrorKind::Nom(ErrorKind::Alt),
+            )))
+        }
+    }
 }
 
 // Meta data parsers
-fn parse_metadata_section(input: &str) -> IResult<&str, Vec<MetaDataField>> {
-    let metadata_parser = branch::alt((
+fn parse_metadata_field<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<'a, MetaDataField<'a>> {
+    branch::alt((
         author::parse_author_data,
         title::parse_title_data,
         basename::parse_basename_data,
-        status::parse_status_data,
+        |input| status::parse_status_data(input, options.dialect),
         allow_comments::parse_allow_comments_data,
         allow_pings::parse_allow_pings_data,
         convert_breaks::parse_convert_breaks_data,
         primary_category::parse_primary_category_data,
         category::parse_category_data,
-        date::parse_date_data,
+        |input| date::parse_date_data(input, options.date_formats),
         tags::parse_tags_data,
         no_entry::parse_no_entry_data,
         image::parse_image_data,
-    ));
+        unique_url::parse_unique_url_data,
+        unless_rejected(options, unknown::parse_unknown_metadata_data),
+    ))(input)
+}
 
+fn parse_metadata_section<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<'a, Vec<(&'a str, MetaDataField<'a>)>> {
     sequence::terminated(
-        multi::many0(metadata_parser),
-        bytes::complete::tag("-----\n"),
+        multi::many0(combinator::consumed(|input| {
+            parse_metadata_field(input, options)
+        })),
+        sequence::terminated(bytes::complete::tag("-----"), utils::line_ending),
     )(input)
 }
 
 // Multi-line data parsers
-fn parse_multiline_data_section(input: &str) -> IResult<&str, Vec<MultiLineField>> {
+fn parse_multiline_data_section<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<'a, Vec<(&'a str, MultiLineField<'a>)>> {
     let multiline_data_parser = branch::alt((
         body::parse_body_data,
         extended_body::parse_extended_body_data,
         excerpt::parse_excerpt_data,
         keywords::parse_keywords_data,
-        comment::parse_comment_data,
-        ping::parse_ping_data,
+        |input| comment::parse_comment_data(input, options.date_formats),
+        |input| ping::parse_ping_data(input, options.date_formats),
+        pinged_urls::parse_pinged_urls_data,
+        unless_rejected(options, unknown::parse_unknown_multiline_data),
     ));
 
-    multi::many0(multiline_data_parser)(input)
+    multi::many0(combinator::consumed(multiline_data_parser))(input)
 }
 
 // MTIF parser
-fn parse_mtif_entry(input: &str) -> IResult<&str, RawMTIFEntry> {
-    let (input, metadata) = parse_metadata_section(input)?;
-    let (input, multiline_data) = parse_multiline_data_section(input)?;
+fn parse_mtif_entry<'a>(input: &'a str, options: &ParseOptions) -> IResult<'a, RawMTIFEntry<'a>> {
+    let entry_start = input;
+    let (input, metadata) = parse_metadata_section(input, options)?;
+    let (metadata_sources, metadata): (Vec<_>, Vec<_>) = metadata.into_iter().unzip();
+    let Some(date) = metadata.iter().find_map(|m| match m {
+        MetaDataField::Date(date, utc_offset) => Some((*date, *utc_offset)),
+        _ => None,
+    }) else {
+        return Err(nom::Err::Failure(ParseError::new(
+            entry_start,
+            ParseErrorKind::MissingDate,
+        )));
+    };
+    let (input, multiline_data) = parse_multiline_data_section(input, options)?;
+    let (multiline_sources, multiline_data) = multiline_data.into_iter().unzip();
     let (input, _) = bytes::complete::tag("--------")(input)?;
 
     Ok((
         input,
         RawMTIFEntry {
+            source: &entry_start[..entry_start.len() - input.len()],
+            date,
             metadata,
+            metadata_sources,
             multiline_data,
+            multiline_sources,
         },
     ))
 }
 
-pub fn parse_mtif(input: &str) -> IResult<&str, Vec<RawMTIFEntry>> {
-    terminated(
-        multi::separated_list0(newline, parse_mtif_entry),
-        terminated(opt(newline), eof),
-    )(input)
+// Locates errors in the input. The line index is built for the first error
+// and reused for the next ones, so that recovering from many broken entries
+// does not rescan the input each time.
+struct ErrorLocator<'a> {
+    input: &'a str,
+    line_index: OnceCell<LineIndex<'a>>,
+}
+
+impl<'a> ErrorLocator<'a> {
+    fn new(input: &'a str) -> Self {
+        ErrorLocator {
+            input,
+            line_index: OnceCell::new(),
+        }
+    }
+
+    fn locate(&self, at: &str, entry: usize) -> ErrorLocation {
+        self.line_index
+            .get_or_init(|| LineIndex::new(self.input))
+            .error_location(at, entry)
+    }
+}
+
+fn parse_entry_at<'a>(
+    locator: &ErrorLocator<'a>,
+    rest: &'a str,
+    options: &ParseOptions,
+    index: usize,
+) -> Result<(&'a str, RawMTIFEntry<'a>), MTIFError> {
+    parse_mtif_entry(rest, options).map_err(|e| match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.into_mtif_error(locator, index),
+        nom::Err::Incomplete(_) => {
+            let input = locator.input;
+            MTIFError::MissingSeparator(locator.locate(&input[input.len()..], index))
+        }
+    })
+}
+
+// Entries are separated by a single line ending; anything else after the
+// terminator, including blank lines at the end, is rejected.
+fn skip_entry_separator<'a>(
+    locator: &ErrorLocator<'a>,
+    remaining: &'a str,
+    index: usize,
+) -> Result<&'a str, MTIFError> {
+    match utils::line_ending(remaining) {
+        Ok((next, _)) if next.is_empty() || !next.trim().is_empty() => Ok(next),
+        Err(_) if remaining.is_empty() => Ok(remaining),
+        trailing => Err(MTIFError::TrailingInput(
+            locator.locate(trailing.map_or(remaining, |(next, _)| next), index),
+        )),
+    }
+}
+
+// Returns the input after the next `--------` line, or the empty rest of the
+// input if there is none.
+fn skip_past_terminator(input: &str) -> &str {
+    let mut rest = input;
+    while !rest.is_empty() {
+        let line_end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+        let (line, next) = rest.split_at(line_end);
+        rest = utils::line_ending(next).map_or(next, |(next, _)| next);
+        if line == "--------" {
+            break;
+        }
+    }
+    rest
+}
+
+// Whether `input` starts with a metadata field, the only kind of line an
+// entry can start with.
+fn starts_entry(input: &str, options: &ParseOptions) -> bool {
+    parse_metadata_field(input, options).is_ok()
+}
+
+// Skips the first line of `input` and any lines after it that cannot start
+// an entry.
+fn skip_to_entry_start<'a>(input: &'a str, options: &ParseOptions) -> &'a str {
+    let mut rest = input;
+    loop {
+        let line_end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+        let next = &rest[line_end..];
+        rest = utils::line_ending(next).map_or(next, |(next, _)| next);
+        if rest.is_empty() || starts_entry(rest, options) {
+            return rest;
+        }
+    }
+}
+
+pub fn parse_mtif<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<Vec<RawMTIFEntry<'a>>, MTIFError> {
+    let locator = ErrorLocator::new(input);
+    let mut entries = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (remaining, entry) = parse_entry_at(&locator, rest, options, entries.len())?;
+        entries.push(entry);
+        rest = skip_entry_separator(&locator, remaining, entries.len())?;
+    }
+
+    Ok(entries)
+}
+
+// Splits `input` into about `parts` slices of whole entries. Only a
+// `--------` line right after a `-----` line is taken as an entry end: that
+// `-----` always closes a section, whereas a lone `--------` may be body text.
+#[cfg(feature = "rayon")]
+pub fn split_entries(input: &str, parts: usize) -> Vec<&str> {
+    let chunk_len = input.len() / parts.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = input;
+    while rest.len() > chunk_len {
+        let Some(end) = next_entry_end(rest, chunk_len) else {
+            break;
+        };
+        let (chunk, next) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = next;
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest);
+    }
+
+    chunks
+}
+
+// The offset just past the line ending of the first entry terminator that
+// ends at or after `min_end`.
+#[cfg(feature = "rayon")]
+fn next_entry_end(input: &str, min_end: usize) -> Option<usize> {
+    // A terminator and its line ending take at most ten bytes.
+    let from = (min_end.saturating_sub(10)..=input.len()).find(|&i| input.is_char_boundary(i))?;
+    input[from..]
+        .match_indices("--------")
+        .find_map(|(i, terminator)| {
+            let at = from + i;
+            let section_end =
+                utils::strip_line_ending_suffix(&input[..at])?.strip_suffix("-----")?;
+            if !(section_end.is_empty() || section_end.ends_with(['\n', '\r'])) {
+                return None;
+            }
+            let end = match utils::line_ending(&input[at + terminator.len()..]) {
+                Ok((next, _)) => input.len() - next.len(),
+                Err(_) if at + terminator.len() == input.len() => input.len(),
+                Err(_) => return None,
+            };
+            (end >= min_end).then_some(end)
+        })
+}
+
+/// Like `parse_mtif`, but on an error skips to the line after the next
+/// `--------` and carries on. An error at the start of an entry or after its
+/// terminator only skips to the next line that can start an entry. Each error
+/// comes with the index of the broken entry and the slice of the input that
+/// was skipped.
+pub fn parse_mtif_recovering<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> (Vec<RawMTIFEntry<'a>>, Vec<(usize, &'a str, MTIFError)>) {
+    let locator = ErrorLocator::new(input);
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut index = 0;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (error, next) = match parse_entry_at(&locator, rest, options, index) {
+            Ok((remaining, entry)) => {
+                entries.push(entry);
+                index += 1;
+                match skip_entry_separator(&locator, remaining, index) {
+                    Ok(next) => {
+                        rest = next;
+                        continue;
+                    }
+                    Err(error) => {
+                        rest = utils::line_ending(remaining).map_or(remaining, |(next, _)| next);
+                        (error, skip_to_entry_start(rest, options))
+                    }
+                }
+            }
+            Err(error) if !starts_entry(rest, options) => {
+                (error, skip_to_entry_start(rest, options))
+            }
+            Err(error) => (error, skip_past_terminator(rest)),
+        };
+
+        errors.push((index, &rest[..rest.len() - next.len()], error));
+        index += 1;
+        rest = next;
+    }
+
+    (entries, errors)
 }
 
 /*
@@ -153,12 +460,15 @@ mod tests {
     #[test]
     fn test_parse_metadata_section() {
         assert_eq!(
-            parse_metadata_section("AUTHOR: Foo Bar\nTITLE: Baz Qux\n-----\n"),
+            parse_metadata_section(
+                "AUTHOR: Foo Bar\nTITLE: Baz Qux\n-----\n",
+                &ParseOptions::default()
+            ),
             Ok((
                 "",
                 vec![
-                    MetaDataField::Author("Foo Bar"),
-                    MetaDataField::Title("Baz Qux")
+                    ("AUTHOR: Foo Bar\n", MetaDataField::Author("Foo Bar")),
+                    ("TITLE: Baz Qux\n", MetaDataField::Title("Baz Qux"))
                 ]
             ))
         );
@@ -167,12 +477,135 @@ mod tests {
     #[test]
     fn test_parse_mtif() {
         let contents = fs::read_to_string("./example/example.txt").unwrap();
-        let (rest, entries) = parse_mtif(&contents).unwrap();
-        assert_eq!(rest, "");
+        let entries = parse_mtif(&contents, &ParseOptions::default()).unwrap();
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].metadata.len(), 6);
         assert_eq!(entries[0].multiline_data.len(), 5);
         assert_eq!(entries[1].metadata.len(), 5);
         assert_eq!(entries[1].multiline_data.len(), 3);
+
+        assert!(entries[0].source.starts_with("TITLE: A dummy title\n"));
+        assert!(entries[0].source.ends_with("\n--------"));
+        assert_eq!(entries[0].metadata_sources[0], "TITLE: A dummy title\n");
+        assert_eq!(entries[0].multiline_sources.len(), 5);
+        assert!(entries[0].multiline_sources[0].starts_with("BODY:\n"));
+        assert!(entries[0].multiline_sources[0].ends_with("\n-----\n"));
+    }
+
+    fn error_at(
+        kind: fn(ErrorLocation) -> MTIFError,
+        line: usize,
+        column: usize,
+        entry: usize,
+        source_line: &str,
+    ) -> MTIFError {
+        kind(ErrorLocation {
+            line,
+            column,
+            entry,
+            source_line: source_line.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_parse_mtif_errors() {
+        let entry = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n-----\n--------\n";
+
+        assert_eq!(
+            parse_mtif(
+                &format!("{entry}TITLE: foo\nUNKNOWN: bar\n"),
+                &ParseOptions::default()
+            )
+            .err(),
+            Some(error_at(
+                MTIFError::UnexpectedField,
+                8,
+                1,
+                1,
+                "UNKNOWN: bar"
+            ))
+        );
+        assert_eq!(
+            parse_mtif("TITLE: foo\n-----\n--------", &ParseOptions::default()).err(),
+            Some(error_at(MTIFError::MissingDate, 1, 1, 0, "TITLE: foo"))
+        );
+        assert_eq!(
+            parse_mtif("DATE: yesterday\n-----\n--------", &ParseOptions::default()).err(),
+            Some(error_at(MTIFError::InvalidDate, 1, 7, 0, "DATE: yesterday"))
+        );
+        assert_eq!(
+            parse_mtif(
+                "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n--------",
+                &ParseOptions::default()
+            )
+            .err(),
+            Some(error_at(MTIFError::MissingSeparator, 4, 1, 0, "body"))
+        );
+        assert_eq!(
+            parse_mtif(&format!("{entry}\n"), &ParseOptions::default()).err(),
+            Some(error_at(MTIFError::TrailingInput, 7, 1, 1, ""))
+        );
+    }
+
+    #[test]
+    fn test_parse_mtif_recovering() {
+        let entry = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n-----\n--------\n";
+        let broken = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n";
+
+        let contents = format!("{entry}{broken}");
+        let (entries, errors) = parse_mtif_recovering(&contents, &ParseOptions::default());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 1);
+        assert_eq!(errors[0].1, broken);
+        assert!(matches!(errors[0].2, MTIFError::MissingSeparator(_)));
+
+        let contents = format!("{entry}--------foo\n{entry}{entry}");
+        let (entries, errors) = parse_mtif_recovering(&contents, &ParseOptions::default());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, "--------foo\n");
+        assert!(matches!(errors[0].2, MTIFError::UnexpectedField(_)));
+
+        let contents = format!("{}{entry}", entry.replace("--------\n", "--------junk\n"));
+        let (entries, errors) = parse_mtif_recovering(&contents, &ParseOptions::default());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, "junk\n");
+        assert!(matches!(errors[0].2, MTIFError::TrailingInput(_)));
+    }
+
+    #[test]
+    fn test_parse_mtif_recovering_junk_between_entries() {
+        let entry = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n-----\n--------\n";
+
+        let contents = format!("{entry}junk line\n\nmore junk\n{entry}{entry}");
+        let (entries, errors) = parse_mtif_recovering(&contents, &ParseOptions::default());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 1);
+        assert_eq!(errors[0].1, "junk line\n\nmore junk\n");
+        assert!(matches!(errors[0].2, MTIFError::UnexpectedField(_)));
+
+        let contents = format!("junk line\n{entry}");
+        let (entries, errors) = parse_mtif_recovering(&contents, &ParseOptions::default());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(errors[0].1, "junk line\n");
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_split_entries() {
+        let entry = "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nbody\n--------\n-----\n--------\n";
+        let contents = entry.repeat(4);
+        assert_eq!(split_entries(&contents, 4), vec![entry; 4]);
+        assert_eq!(split_entries(&contents, 2), vec![entry.repeat(2); 2]);
+        assert_eq!(split_entries(&contents, 1), vec![contents.as_str()]);
+        assert_eq!(split_entries("", 4), vec![""]);
+
+        let contents = entry.replace('\n', "\r\n").repeat(2);
+        assert_eq!(split_entries(&contents, 2).len(), 2);
+        let contents = format!("{entry}{}", entry.trim_end());
+        assert_eq!(split_entries(&contents, 2), vec![entry, entry.trim_end()]);
     }
 }
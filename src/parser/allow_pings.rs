@@ -1,11 +1,11 @@
-use nom::{bytes, IResult};
+use nom::bytes;
 
-use super::MetaDataField;
+use super::{IResult, MetaDataField};
 
 use super::utils::parse_num_bool_flag;
 
 // ALLOW PINGS: 0|1\n
-pub fn parse_allow_pings_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_allow_pings_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("ALLOW PINGS: ")(input)?;
     let (input, flag) = parse_num_bool_flag(input)?;
 
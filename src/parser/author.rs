@@ -1,13 +1,10 @@
-use nom::{
-    bytes::{self},
-    IResult,
-};
+use nom::bytes::{self};
 
 use super::utils::parse_until_line_ending;
-use super::MetaDataField;
+use super::{IResult, MetaDataField};
 
 // AUTHOR: <text>\n
-pub fn parse_author_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_author_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("AUTHOR: ")(input)?;
     let (input, text) = parse_until_line_ending(input)?;
 
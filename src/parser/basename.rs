@@ -1,13 +1,10 @@
-use super::MetaDataField;
-use nom::{
-    bytes::{self},
-    IResult,
-};
+use super::{IResult, MetaDataField};
+use nom::bytes::{self};
 
 use super::utils::parse_until_line_ending;
 
 // BASENAME: <text>\n
-pub fn parse_basename_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_basename_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("BASENAME: ")(input)?;
     let (input, text) = parse_until_line_ending(input)?;
 
@@ -1,10 +1,14 @@
-use nom::{
-    branch, bytes, character::complete::newline, multi::many0, sequence::terminated, IResult,
-};
+use nom::{branch, bytes, combinator, multi::many0, sequence};
+
+use crate::model::CommentStatus;
+use crate::options::DateFormats;
 
 use super::{
-    utils::{parse_date_value, parse_multiline_text, parse_until_line_ending},
-    MultiLineField,
+    status::comment_status_from_value,
+    utils::{
+        line_ending, parse_date_line, parse_multiline_text, parse_until_line_ending, DateValue,
+    },
+    IResult, MultiLineField,
 };
 
 enum CommentField<'a> {
@@ -12,58 +16,78 @@ enum CommentField<'a> {
     Url(&'a str),
     Author(&'a str),
     Ip(&'a str),
-    Date(time::PrimitiveDateTime),
+    Date(DateValue),
+    Status(CommentStatus),
 }
 
-fn parse_email_field(input: &str) -> IResult<&str, CommentField> {
+fn parse_email_field(input: &str) -> IResult<'_, CommentField<'_>> {
     let (input, _) = bytes::complete::tag("EMAIL: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, CommentField::Email(contents)))
 }
 
-fn parse_url_field(input: &str) -> IResult<&str, CommentField> {
+fn parse_url_field(input: &str) -> IResult<'_, CommentField<'_>> {
     let (input, _) = bytes::complete::tag("URL: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, CommentField::Url(contents)))
 }
 
-fn parse_author_field(input: &str) -> IResult<&str, CommentField> {
+fn parse_author_field(input: &str) -> IResult<'_, CommentField<'_>> {
     let (input, _) = bytes::complete::tag("AUTHOR: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, CommentField::Author(contents)))
 }
 
-fn parse_ip_field(input: &str) -> IResult<&str, CommentField> {
+fn parse_ip_field(input: &str) -> IResult<'_, CommentField<'_>> {
     let (input, _) = bytes::complete::tag("IP: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, CommentField::Ip(contents)))
 }
 
-fn parse_date_field(input: &str) -> IResult<&str, CommentField> {
-    let (input, _) = bytes::complete::tag("DATE: ")(input)?;
-    let (input, date) = terminated(parse_date_value, newline)(input)?;
+fn parse_date_field<'a>(
+    formats: DateFormats,
+) -> impl FnMut(&'a str) -> IResult<'a, CommentField<'a>> {
+    move |input| {
+        let (input, _) = bytes::complete::tag("DATE: ")(input)?;
+        let (input, date) = parse_date_line(formats)(input)?;
+
+        Ok((input, CommentField::Date(date)))
+    }
+}
+
+// STATUS: approved|pending|spam\n
+// Any other value is not a status; the line then starts the text.
+fn parse_status_field(input: &str) -> IResult<'_, CommentField<'_>> {
+    let (input, _) = bytes::complete::tag("STATUS: ")(input)?;
+    let (input, status) =
+        combinator::map_opt(parse_until_line_ending, comment_status_from_value)(input)?;
 
-    Ok((input, CommentField::Date(date)))
+    Ok((input, CommentField::Status(status)))
 }
 
-fn parse_comment_fields(input: &str) -> IResult<&str, Vec<CommentField>> {
+fn parse_comment_fields(input: &str, formats: DateFormats) -> IResult<'_, Vec<CommentField<'_>>> {
     many0(branch::alt((
         parse_email_field,
         parse_url_field,
         parse_author_field,
         parse_ip_field,
-        parse_date_field,
+        parse_date_field(formats),
+        parse_status_field,
     )))(input)
 }
 
-pub fn parse_comment_data(input: &str) -> IResult<&str, MultiLineField> {
-    let (input, _) = bytes::complete::tag("COMMENT:\n")(input)?;
-    let (input, fields) = parse_comment_fields(input)?;
+pub fn parse_comment_data(input: &str, formats: DateFormats) -> IResult<'_, MultiLineField<'_>> {
+    let (input, _) = sequence::terminated(bytes::complete::tag("COMMENT:"), line_ending)(input)?;
+    let (input, fields) = parse_comment_fields(input, formats)?;
     let (input, text) = parse_multiline_text(input)?;
+    let date = fields.iter().find_map(|f| match f {
+        CommentField::Date(date) => Some(*date),
+        _ => None,
+    });
 
     let comment = MultiLineField::Comment {
         author: fields.iter().find_map(|f| match f {
@@ -82,11 +106,13 @@ pub fn parse_comment_data(input: &str) -> IResult<&str, MultiLineField> {
             CommentField::Ip(ip) => Some(*ip),
             _ => None,
         }),
-        date: fields.iter().find_map(|f| match f {
-            CommentField::Date(date) => Some(*date),
+        date: date.map(|(date, _)| date),
+        utc_offset: date.and_then(|(_, utc_offset)| utc_offset),
+        status: fields.iter().find_map(|f| match f {
+            CommentField::Status(status) => Some(*status),
             _ => None,
         }),
-        text: text,
+        text,
     };
 
     Ok((input, comment))
@@ -99,8 +125,8 @@ mod tests {
     #[test]
     fn test_parse_comment_without_field_data() {
         let target_text = "COMMENT:\nAUTHOR: author\nEMAIL: sample@example.com\nURL: https://example.com/\nIP: 192.0.2.0\nDATE: 12/31/2023 01:34:56 PM\nFoo Bar\nBaz Qux\n\n-----\n";
-        let (rest, result) = parse_comment_data(target_text).unwrap();
-        if rest != "" {
+        let (rest, result) = parse_comment_data(target_text, DateFormats::default()).unwrap();
+        if !rest.is_empty() {
             panic!("rest is not empty: {}", rest);
         }
         if let MultiLineField::Comment {
@@ -109,6 +135,8 @@ mod tests {
             url,
             ip,
             date,
+            utc_offset,
+            status,
             text,
         } = result
         {
@@ -117,7 +145,55 @@ mod tests {
             assert_eq!(url, Some("https://example.com/"));
             assert_eq!(ip, Some("192.0.2.0"));
             assert_eq!(date, Some(time::macros::datetime!(2023-12-31 13:34:56)));
+            assert_eq!(utc_offset, None);
+            assert_eq!(status, None);
             assert_eq!(text, "Foo Bar\nBaz Qux\n");
         }
     }
+
+    #[test]
+    fn test_parse_comment_status() {
+        let target_text = "COMMENT:\nSTATUS: approved\nFoo Bar\n-----\n";
+        let (rest, result) = parse_comment_data(target_text, DateFormats::default()).unwrap();
+        assert_eq!(rest, "");
+        if let MultiLineField::Comment { status, text, .. } = result {
+            assert_eq!(status, Some(CommentStatus::Approved));
+            assert_eq!(text, "Foo Bar");
+        } else {
+            panic!("not a comment: {:?}", result);
+        }
+
+        for (value, expected) in [
+            ("Pending", CommentStatus::Pending),
+            ("0", CommentStatus::Pending),
+            ("SPAM", CommentStatus::Spam),
+        ] {
+            let target_text = format!("COMMENT:\nSTATUS: {}\nFoo Bar\n-----\n", value);
+            let (_, result) = parse_comment_data(&target_text, DateFormats::default()).unwrap();
+            assert!(matches!(
+                result,
+                MultiLineField::Comment { status: Some(status), .. } if status == expected
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_comment_text_starting_with_status() {
+        let target_text = "COMMENT:\nAUTHOR: a\nSTATUS: this is my status update\n-----\n";
+        let (rest, result) = parse_comment_data(target_text, DateFormats::default()).unwrap();
+        assert_eq!(rest, "");
+        if let MultiLineField::Comment {
+            author,
+            status,
+            text,
+            ..
+        } = result
+        {
+            assert_eq!(author, Some("a"));
+            assert_eq!(status, None);
+            assert_eq!(text, "STATUS: this is my status update");
+        } else {
+            panic!("not a comment: {:?}", result);
+        }
+    }
 }
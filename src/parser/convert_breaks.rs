@@ -1,35 +1,30 @@
-use nom::{branch, bytes, character, combinator, sequence, IResult};
+use nom::{bytes, combinator};
 
 use crate::model::ConvertBreaks;
 
-use super::MetaDataField;
+use super::{utils::parse_until_line_ending, IResult, MetaDataField};
 
-// CONVERT BREAKS: 0|1|markdown_with_smartypants|markdown|richtext|textile_2\n
-pub fn parse_convert_breaks_data(input: &str) -> IResult<&str, MetaDataField> {
-    let (input, _) = bytes::complete::tag("CONVERT BREAKS: ")(input)?;
-    let value_parser = sequence::terminated(
-        branch::alt((
-            bytes::complete::tag("0"),
-            bytes::complete::tag("1"),
-            // markdownの方が先にマッチしてしまうと困るので、長い方からマッチさせる
-            bytes::complete::tag("markdown_with_smartypants"),
-            bytes::complete::tag("markdown"),
-            bytes::complete::tag("richtext"),
-            bytes::complete::tag("textile_2"),
-        )),
-        character::complete::newline,
-    );
-    let value_to_enum = |value: &str| match value.to_ascii_lowercase().as_str() {
-        "0" => ConvertBreaks::None,
-        "1" => ConvertBreaks::Convert,
-        "markdown" => ConvertBreaks::Markdown,
-        "markdown_with_smartypants" => ConvertBreaks::MarkdownWithSmartypants,
-        "richtext" => ConvertBreaks::RichText,
-        "textile_2" => ConvertBreaks::Textile2,
-        _ => unreachable!(),
-    };
+fn convert_breaks_from_value(value: &str) -> Option<ConvertBreaks<'_>> {
+    match value {
+        "0" => Some(ConvertBreaks::None),
+        "1" => Some(ConvertBreaks::Convert),
+        "markdown" => Some(ConvertBreaks::Markdown),
+        "markdown_with_smartypants" => Some(ConvertBreaks::MarkdownWithSmartypants),
+        "richtext" => Some(ConvertBreaks::RichText),
+        "textile_2" => Some(ConvertBreaks::Textile2),
+        "__default__" => Some(ConvertBreaks::Default),
+        "hatena" => Some(ConvertBreaks::HatenaNotation),
+        "wysiwyg" => Some(ConvertBreaks::Wysiwyg),
+        "" => None,
+        value => Some(ConvertBreaks::Other(value)),
+    }
+}
 
-    let (input, convert_breaks) = combinator::map(value_parser, value_to_enum)(input)?;
+// CONVERT BREAKS: 0|1|markdown_with_smartypants|markdown|richtext|textile_2|__default__|hatena|wysiwyg|<filter>\n
+pub fn parse_convert_breaks_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
+    let (input, _) = bytes::complete::tag("CONVERT BREAKS: ")(input)?;
+    let (input, convert_breaks) =
+        combinator::map_opt(parse_until_line_ending, convert_breaks_from_value)(input)?;
 
     Ok((input, MetaDataField::ConvertBreaks(convert_breaks)))
 }
@@ -68,4 +63,35 @@ mod tests {
             Ok(("", MetaDataField::ConvertBreaks(ConvertBreaks::Textile2)))
         );
     }
+
+    #[test]
+    fn test_parse_convert_breaks_data_extensions() {
+        assert_eq!(
+            parse_convert_breaks_data("CONVERT BREAKS: __default__\n"),
+            Ok(("", MetaDataField::ConvertBreaks(ConvertBreaks::Default)))
+        );
+        assert_eq!(
+            parse_convert_breaks_data("CONVERT BREAKS: hatena\n"),
+            Ok((
+                "",
+                MetaDataField::ConvertBreaks(ConvertBreaks::HatenaNotation)
+            ))
+        );
+        assert_eq!(
+            parse_convert_breaks_data("CONVERT BREAKS: wysiwyg\n"),
+            Ok(("", MetaDataField::ConvertBreaks(ConvertBreaks::Wysiwyg)))
+        );
+    }
+
+    #[test]
+    fn test_parse_convert_breaks_data_other() {
+        assert_eq!(
+            parse_convert_breaks_data("CONVERT BREAKS: wiki\n"),
+            Ok((
+                "",
+                MetaDataField::ConvertBreaks(ConvertBreaks::Other("wiki"))
+            ))
+        );
+        assert!(parse_convert_breaks_data("CONVERT BREAKS: \n").is_err());
+    }
 }
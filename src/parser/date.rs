@@ -1,52 +1,149 @@
-use super::{utils::parse_date_value, MetaDataField};
-use nom::{
-    bytes::{self},
-    character::complete::newline,
-    sequence::terminated,
-    IResult,
-};
-
-// DATE: MM/DD/YYYY HH:MM:SS AM|PM?\n
-pub fn parse_date_data(input: &str) -> IResult<&str, MetaDataField> {
+use super::{utils::parse_date_line, IResult, MetaDataField};
+use crate::options::DateFormats;
+use nom::bytes::{self};
+
+// DATE: MM/DD/YYYY HH:MM:SS AM|PM?\n, or another of the enabled formats
+pub fn parse_date_data(input: &str, formats: DateFormats) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("DATE: ")(input)?;
-    let (input, date) = terminated(parse_date_value, newline)(input)?;
+    let (input, (date, utc_offset)) = parse_date_line(formats)(input)?;
 
-    Ok((input, MetaDataField::Date(date)))
+    Ok((input, MetaDataField::Date(date, utc_offset)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::{ParseError, ParseErrorKind};
 
     #[test]
     fn test_parse_date_data() {
         assert_eq!(
-            parse_date_data("DATE: 12/31/2012 12:34:56\n"),
+            parse_date_data("DATE: 12/31/2012 12:34:56\n", DateFormats::default()),
+            Ok((
+                "",
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 12:34:56), None)
+            ))
+        );
+        assert_eq!(
+            parse_date_data("DATE: 12/31/2012 01:34:56 AM\n", DateFormats::default()),
+            Ok((
+                "",
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 01:34:56), None)
+            ))
+        );
+        assert_eq!(
+            parse_date_data("DATE: 12/31/2012 01:34:56 PM\n", DateFormats::default()),
+            Ok((
+                "",
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 13:34:56), None)
+            ))
+        );
+        assert_eq!(
+            parse_date_data("DATE: 12/31/2012 13:34:56\n", DateFormats::default()),
             Ok((
                 "",
-                MetaDataField::Date(time::macros::datetime!(2012-12-31 12:34:56))
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 13:34:56), None)
             ))
         );
+    }
+
+    #[test]
+    fn test_parse_date_data_midnight_and_noon() {
         assert_eq!(
-            parse_date_data("DATE: 12/31/2012 12:34:56 AM\n"),
+            parse_date_data("DATE: 12/31/2012 12:34:56 AM\n", DateFormats::default()),
             Ok((
                 "",
-                MetaDataField::Date(time::macros::datetime!(2012-12-31 12:34:56))
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 00:34:56), None)
             ))
         );
         assert_eq!(
-            parse_date_data("DATE: 12/31/2012 01:34:56 PM\n"),
+            parse_date_data("DATE: 12/31/2012 12:34:56 PM\n", DateFormats::default()),
             Ok((
                 "",
-                MetaDataField::Date(time::macros::datetime!(2012-12-31 13:34:56))
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 12:34:56), None)
             ))
         );
         assert_eq!(
-            parse_date_data("DATE: 12/31/2012 13:34:56\n"),
+            parse_date_data("DATE: 12/31/2012 11:59:59 PM\n", DateFormats::default()),
             Ok((
                 "",
-                MetaDataField::Date(time::macros::datetime!(2012-12-31 13:34:56))
+                MetaDataField::Date(time::macros::datetime!(2012-12-31 23:59:59), None)
             ))
         );
     }
+
+    #[test]
+    fn test_parse_date_data_invalid() {
+        for input in [
+            "DATE: 00/31/2012 12:34:56\n",
+            "DATE: 13/31/2012 12:34:56\n",
+            "DATE: 02/30/2012 12:34:56\n",
+            "DATE: 12/31/2012 24:34:56\n",
+            "DATE: 12/31/2012 12:60:56\n",
+            "DATE: 12/31/2012 13:34:56 PM\n",
+            "DATE: 12/31/2012 13:34:56 AM\n",
+        ] {
+            assert_eq!(
+                parse_date_data(input, DateFormats::default()),
+                Err(nom::Err::Failure(ParseError::new(
+                    &input[6..],
+                    ParseErrorKind::InvalidDate
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_date_data_formats() {
+        let all = crate::ParseOptions::new()
+            .date_formats(&crate::options::DateFormat::ALL)
+            .date_formats;
+        for (input, expected, utc_offset) in [
+            (
+                "DATE: 1/5/2004 3:04:05 PM\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                None,
+            ),
+            (
+                "DATE: 01/05/2004 03:04:05 pm\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                None,
+            ),
+            (
+                "DATE: 2004-01-05T15:04:05+09:00\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                Some(time::macros::offset!(+9)),
+            ),
+            (
+                "DATE: 2004-01-05 15:04:05-03:30\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                Some(time::macros::offset!(-3:30)),
+            ),
+            (
+                "DATE: 2004-01-05T15:04:05Z\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                Some(time::UtcOffset::UTC),
+            ),
+            (
+                "DATE: 2004-01-05T15:04:05\n",
+                time::macros::datetime!(2004-01-05 15:04:05),
+                None,
+            ),
+        ] {
+            assert_eq!(
+                parse_date_data(input, all),
+                Ok(("", MetaDataField::Date(expected, utc_offset))),
+                "{input:?}"
+            );
+            assert!(parse_date_data(input, DateFormats::default()).is_err());
+        }
+
+        for input in [
+            "DATE: 2004-13-05T15:04:05\n",
+            "DATE: 2004-01-05T15:04:05+09:75\n",
+            "DATE: 1/5/2004 13:04:05 pm\n",
+        ] {
+            assert!(parse_date_data(input, all).is_err(), "{input:?}");
+        }
+    }
 }
@@ -1,9 +1,12 @@
-use nom::{bytes, IResult};
+use nom::{bytes, sequence};
 
-use super::{utils::parse_multiline_text, MultiLineField};
+use super::{
+    utils::{line_ending, parse_multiline_text},
+    IResult, MultiLineField,
+};
 
-pub fn parse_excerpt_data(input: &str) -> IResult<&str, MultiLineField> {
-    let (input, _) = bytes::complete::tag("EXCERPT:\n")(input)?;
+pub fn parse_excerpt_data(input: &str) -> IResult<'_, MultiLineField<'_>> {
+    let (input, _) = sequence::terminated(bytes::complete::tag("EXCERPT:"), line_ending)(input)?;
     let (input, text) = parse_multiline_text(input)?;
 
     Ok((input, MultiLineField::Excerpt(text)))
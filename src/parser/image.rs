@@ -1,14 +1,11 @@
-use nom::{
-    bytes::{self},
-    IResult,
-};
+use nom::bytes::{self};
 
 use super::utils::parse_until_line_ending;
-use super::MetaDataField;
+use super::{IResult, MetaDataField};
 
 // Hatena export format extension.
 // IMAGE: <text>\n
-pub fn parse_image_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_image_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("IMAGE: ")(input)?;
     let (input, text) = parse_until_line_ending(input)?;
 
@@ -1,10 +1,10 @@
-use nom::{bytes, IResult};
+use nom::{bytes, sequence};
 
-use super::MetaDataField;
+use super::{utils::line_ending, IResult, MetaDataField};
 
 // NO ENTRY: 1\n
-pub fn parse_no_entry_data(input: &str) -> IResult<&str, MetaDataField> {
-    let (input, _) = bytes::complete::tag("NO ENTRY: 1\n")(input)?;
+pub fn parse_no_entry_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
+    let (input, _) = sequence::terminated(bytes::complete::tag("NO ENTRY: 1"), line_ending)(input)?;
 
     Ok((input, MetaDataField::NoEntry))
 }
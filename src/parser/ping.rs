@@ -1,10 +1,14 @@
-use nom::{
-    branch, bytes, character::complete::newline, multi::many0, sequence::terminated, IResult,
-};
+use nom::{branch, bytes, combinator, multi::many0, sequence};
+
+use crate::model::CommentStatus;
+use crate::options::DateFormats;
 
 use super::{
-    utils::{parse_date_value, parse_multiline_text, parse_until_line_ending},
-    MultiLineField,
+    status::comment_status_from_value,
+    utils::{
+        line_ending, parse_date_line, parse_multiline_text, parse_until_line_ending, DateValue,
+    },
+    IResult, MultiLineField,
 };
 
 enum PingField<'a> {
@@ -12,58 +16,76 @@ enum PingField<'a> {
     Url(&'a str),
     Ip(&'a str),
     BlogName(&'a str),
-    Date(time::PrimitiveDateTime),
+    Date(DateValue),
+    Status(CommentStatus),
 }
 
-fn parse_title_field(input: &str) -> IResult<&str, PingField> {
+fn parse_title_field(input: &str) -> IResult<'_, PingField<'_>> {
     let (input, _) = bytes::complete::tag("TITLE: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, PingField::Title(contents)))
 }
 
-fn parse_url_field(input: &str) -> IResult<&str, PingField> {
+fn parse_url_field(input: &str) -> IResult<'_, PingField<'_>> {
     let (input, _) = bytes::complete::tag("URL: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, PingField::Url(contents)))
 }
 
-fn parse_ip_field(input: &str) -> IResult<&str, PingField> {
+fn parse_ip_field(input: &str) -> IResult<'_, PingField<'_>> {
     let (input, _) = bytes::complete::tag("IP: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, PingField::Ip(contents)))
 }
 
-fn parse_blog_name_field(input: &str) -> IResult<&str, PingField> {
+fn parse_blog_name_field(input: &str) -> IResult<'_, PingField<'_>> {
     let (input, _) = bytes::complete::tag("BLOG NAME: ")(input)?;
     let (input, contents) = parse_until_line_ending(input)?;
 
     Ok((input, PingField::BlogName(contents)))
 }
 
-fn parse_date_field(input: &str) -> IResult<&str, PingField> {
-    let (input, _) = bytes::complete::tag("DATE: ")(input)?;
-    let (input, date) = terminated(parse_date_value, newline)(input)?;
+fn parse_date_field<'a>(formats: DateFormats) -> impl FnMut(&'a str) -> IResult<'a, PingField<'a>> {
+    move |input| {
+        let (input, _) = bytes::complete::tag("DATE: ")(input)?;
+        let (input, date) = parse_date_line(formats)(input)?;
 
-    Ok((input, PingField::Date(date)))
+        Ok((input, PingField::Date(date)))
+    }
 }
 
-fn parse_ping_fields(input: &str) -> IResult<&str, Vec<PingField>> {
+// STATUS: approved|pending|spam\n
+// Any other value is not a status; the line then starts the text.
+fn parse_status_field(input: &str) -> IResult<'_, PingField<'_>> {
+    let (input, _) = bytes::complete::tag("STATUS: ")(input)?;
+    let (input, status) =
+        combinator::map_opt(parse_until_line_ending, comment_status_from_value)(input)?;
+
+    Ok((input, PingField::Status(status)))
+}
+
+fn parse_ping_fields(input: &str, formats: DateFormats) -> IResult<'_, Vec<PingField<'_>>> {
     many0(branch::alt((
         parse_title_field,
         parse_url_field,
         parse_ip_field,
         parse_blog_name_field,
-        parse_date_field,
+        parse_date_field(formats),
+        parse_status_field,
     )))(input)
 }
 
-pub fn parse_ping_data(input: &str) -> IResult<&str, MultiLineField> {
-    let (input, _) = bytes::complete::tag("PING:\n")(input)?;
-    let (input, fields) = parse_ping_fields(input)?;
+pub fn parse_ping_data(input: &str, formats: DateFormats) -> IResult<'_, MultiLineField<'_>> {
+    let (input, _) = sequence::terminated(bytes::complete::tag("PING:"), line_ending)(input)?;
+    let (input, fields) = parse_ping_fields(input, formats)?;
     let (input, text) = parse_multiline_text(input)?;
+    let date = fields.iter().find_map(|f| match f {
+        PingField::Date(date) => Some(*date),
+        _ => None,
+    });
 
     let ping = MultiLineField::Ping {
         title: fields.iter().find_map(|f| match f {
@@ -82,11 +104,13 @@ pub fn parse_ping_data(input: &str) -> IResult<&str, MultiLineField> {
             PingField::BlogName(blog_name) => Some(*blog_name),
             _ => None,
         }),
-        date: fields.iter().find_map(|f| match f {
-            PingField::Date(date) => Some(*date),
+        date: date.map(|(date, _)| date),
+        utc_offset: date.and_then(|(_, utc_offset)| utc_offset),
+        status: fields.iter().find_map(|f| match f {
+            PingField::Status(status) => Some(*status),
             _ => None,
         }),
-        text: text,
+        text,
     };
 
     Ok((input, ping))
@@ -99,8 +123,8 @@ mod tests {
     #[test]
     fn test_parse_ping_without_field_data() {
         let target_text = "PING:\nTITLE: sample title\nURL: https://example.com/\nIP: 192.0.2.0\nBLOG NAME: sample blog name\nDATE: 12/31/2023 01:34:56 PM\nFoo Bar\nBaz Qux\n\n-----\n";
-        let (rest, result) = parse_ping_data(target_text).unwrap();
-        if rest != "" {
+        let (rest, result) = parse_ping_data(target_text, DateFormats::default()).unwrap();
+        if !rest.is_empty() {
             panic!("rest is not empty: {}", rest);
         }
         if let MultiLineField::Ping {
@@ -109,6 +133,8 @@ mod tests {
             ip,
             blog_name,
             date,
+            utc_offset,
+            status,
             text,
         } = result
         {
@@ -117,7 +143,35 @@ mod tests {
             assert_eq!(ip, Some("192.0.2.0"));
             assert_eq!(blog_name, Some("sample blog name"));
             assert_eq!(date, Some(time::macros::datetime!(2023-12-31 13:34:56)));
+            assert_eq!(utc_offset, None);
+            assert_eq!(status, None);
             assert_eq!(text, "Foo Bar\nBaz Qux\n");
         }
     }
+
+    #[test]
+    fn test_parse_ping_status() {
+        let target_text = "PING:\nSTATUS: junk\nFoo Bar\n-----\n";
+        let (rest, result) = parse_ping_data(target_text, DateFormats::default()).unwrap();
+        assert_eq!(rest, "");
+        if let MultiLineField::Ping { status, text, .. } = result {
+            assert_eq!(status, Some(CommentStatus::Spam));
+            assert_eq!(text, "Foo Bar");
+        } else {
+            panic!("not a ping: {:?}", result);
+        }
+    }
+
+    #[test]
+    fn test_parse_ping_text_starting_with_status() {
+        let target_text = "PING:\nTITLE: title\nSTATUS: of the project\n-----\n";
+        let (rest, result) = parse_ping_data(target_text, DateFormats::default()).unwrap();
+        assert_eq!(rest, "");
+        if let MultiLineField::Ping { status, text, .. } = result {
+            assert_eq!(status, None);
+            assert_eq!(text, "STATUS: of the project");
+        } else {
+            panic!("not a ping: {:?}", result);
+        }
+    }
 }
@@ -0,0 +1,41 @@
+use nom::{bytes, sequence};
+
+use super::{
+    utils::{line_ending, parse_multiline_text},
+    IResult, MultiLineField,
+};
+
+// PINGED URLS:\n<url>\n<url>\n...-----\n
+pub fn parse_pinged_urls_data(input: &str) -> IResult<'_, MultiLineField<'_>> {
+    let (input, _) =
+        sequence::terminated(bytes::complete::tag("PINGED URLS:"), line_ending)(input)?;
+    let (input, text) = parse_multiline_text(input)?;
+    let urls = text
+        .split(['\n', '\r'])
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .collect();
+
+    Ok((input, MultiLineField::PingedUrls(urls)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pinged_urls_data() {
+        assert_eq!(
+            parse_pinged_urls_data(
+                "PINGED URLS:\nhttp://www.foo.com/ping\r\nhttp://www.bar.com/ping\n\n-----\n"
+            ),
+            Ok((
+                "",
+                MultiLineField::PingedUrls(vec![
+                    "http://www.foo.com/ping",
+                    "http://www.bar.com/ping"
+                ])
+            ))
+        );
+    }
+}
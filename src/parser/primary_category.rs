@@ -1,11 +1,11 @@
-use nom::{bytes, IResult};
+use nom::bytes;
 
-use super::MetaDataField;
+use super::{IResult, MetaDataField};
 
 use super::utils::parse_until_line_ending;
 
 // PRIMARY CATEGORY: <text>\n
-pub fn parse_primary_category_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_primary_category_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("PRIMARY CATEGORY: ")(input)?;
     let (input, text) = parse_until_line_ending(input)?;
 
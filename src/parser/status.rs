@@ -1,28 +1,35 @@
-use nom::{branch, bytes, character, combinator, sequence, IResult};
+use nom::{bytes, combinator};
 
-use crate::model::Status;
+use crate::model::{CommentStatus, Status};
+use crate::options::Dialect;
 
-use super::MetaDataField;
+use super::{utils::parse_until_line_ending, IResult, MetaDataField};
+
+fn status_from_value(value: &str, dialect: Dialect) -> Option<Status> {
+    match (value.to_ascii_lowercase().as_str(), dialect) {
+        ("draft", _) => Some(Status::Draft),
+        ("publish", _) => Some(Status::Publish),
+        ("future", _) | ("scheduled", Dialect::Hatena) => Some(Status::Future),
+        _ => None,
+    }
+}
+
+// Comment and ping statuses, as written by MT versions and plugins.
+pub fn comment_status_from_value(value: &str) -> Option<CommentStatus> {
+    match value.to_ascii_lowercase().as_str() {
+        "approved" | "1" => Some(CommentStatus::Approved),
+        "pending" | "moderate" | "unapproved" | "0" => Some(CommentStatus::Pending),
+        "spam" | "junk" => Some(CommentStatus::Spam),
+        _ => None,
+    }
+}
 
 // STATUS: Draft|Publish|Future\n
-pub fn parse_status_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_status_data(input: &str, dialect: Dialect) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("STATUS: ")(input)?;
-    let status_tag_parser = sequence::terminated(
-        branch::alt((
-            bytes::complete::tag_no_case("Draft"),
-            bytes::complete::tag_no_case("Publish"),
-            bytes::complete::tag_no_case("Future"),
-        )),
-        character::complete::newline,
-    );
-    let status_str_to_enum = |status: &str| match status.to_ascii_lowercase().as_str() {
-        "draft" => Status::Draft,
-        "publish" => Status::Publish,
-        "future" => Status::Future,
-        _ => unreachable!(),
-    };
-
-    let (input, status) = combinator::map(status_tag_parser, status_str_to_enum)(input)?;
+    let (input, status) = combinator::map_opt(parse_until_line_ending, |value| {
+        status_from_value(value, dialect)
+    })(input)?;
 
     Ok((input, MetaDataField::Status(status)))
 }
@@ -34,16 +41,26 @@ mod tests {
     #[test]
     fn test_parse_status_data() {
         assert_eq!(
-            parse_status_data("STATUS: dRaFT\n"),
+            parse_status_data("STATUS: dRaFT\n", Dialect::MovableType),
             Ok(("", MetaDataField::Status(Status::Draft)))
         );
         assert_eq!(
-            parse_status_data("STATUS: PuBLiSh\n"),
+            parse_status_data("STATUS: PuBLiSh\n", Dialect::MovableType),
             Ok(("", MetaDataField::Status(Status::Publish)))
         );
         assert_eq!(
-            parse_status_data("STATUS: FUTURE\n"),
+            parse_status_data("STATUS: FUTURE\n", Dialect::MovableType),
+            Ok(("", MetaDataField::Status(Status::Future)))
+        );
+    }
+
+    #[test]
+    fn test_parse_status_data_dialects() {
+        assert_eq!(
+            parse_status_data("STATUS: Scheduled\n", Dialect::Hatena),
             Ok(("", MetaDataField::Status(Status::Future)))
         );
+        assert!(parse_status_data("STATUS: Scheduled\n", Dialect::MovableType).is_err());
+        assert!(parse_status_data("STATUS: Draft \n", Dialect::MovableType).is_err());
     }
 }
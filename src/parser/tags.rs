@@ -4,34 +4,34 @@ use nom::{
         self,
         complete::{tag, take_while},
     },
-    character::{self, complete::newline},
+    character::{self},
     multi::separated_list0,
-    sequence, IResult,
+    sequence,
 };
 
-use super::MetaDataField;
+use super::{utils::line_ending, IResult, MetaDataField};
 
-fn parse_quoted_tag_entry(input: &str) -> IResult<&str, &str> {
+fn parse_quoted_tag_entry(input: &str) -> IResult<'_, &str> {
     let (input, _) = character::complete::char('"')(input)?;
-    let (input, contents) = take_while(|c| c != '"' && c != '\n')(input)?;
+    let (input, contents) = take_while(|c| c != '"' && c != '\n' && c != '\r')(input)?;
     let (input, _) = character::complete::char('"')(input)?;
 
-    return Ok((input, contents));
+    Ok((input, contents))
 }
 
-fn parse_non_quoted_tag_entry(input: &str) -> IResult<&str, &str> {
+fn parse_non_quoted_tag_entry(input: &str) -> IResult<'_, &str> {
     let (input, value) = take_while(|c: char| !c.is_whitespace() && c != '\n' && c != ',')(input)?;
 
-    return Ok((input, value));
+    Ok((input, value))
 }
 
-pub fn parse_tags_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_tags_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("TAGS: ")(input)?;
     let tag_entry_parser = branch::alt((parse_quoted_tag_entry, parse_non_quoted_tag_entry));
     let (input, tag_entries) =
-        sequence::terminated(separated_list0(tag(","), tag_entry_parser), newline)(input)?;
+        sequence::terminated(separated_list0(tag(","), tag_entry_parser), line_ending)(input)?;
 
-    return Ok((input, MetaDataField::Tags(tag_entries)));
+    Ok((input, MetaDataField::Tags(tag_entries)))
 }
 
 #[cfg(test)]
@@ -1,14 +1,11 @@
-use nom::{
-    bytes::{self},
-    IResult,
-};
+use nom::bytes::{self};
 
-use super::MetaDataField;
+use super::{IResult, MetaDataField};
 
 use super::utils::parse_until_line_ending;
 
 // TITLE: <text>\n
-pub fn parse_title_data(input: &str) -> IResult<&str, MetaDataField> {
+pub fn parse_title_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
     let (input, _) = bytes::complete::tag("TITLE: ")(input)?;
     let (input, text) = parse_until_line_ending(input)?;
 
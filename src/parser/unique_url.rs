@@ -0,0 +1,28 @@
+use nom::bytes::{self};
+
+use super::utils::parse_until_line_ending;
+use super::{IResult, MetaDataField};
+
+// UNIQUE URL: <text>\n
+pub fn parse_unique_url_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
+    let (input, _) = bytes::complete::tag("UNIQUE URL: ")(input)?;
+    let (input, text) = parse_until_line_ending(input)?;
+
+    Ok((input, MetaDataField::UniqueUrl(text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unique_url_data() {
+        assert_eq!(
+            parse_unique_url_data("UNIQUE URL: https://example.com/2002/01/foo.html\n"),
+            Ok((
+                "",
+                MetaDataField::UniqueUrl("https://example.com/2002/01/foo.html")
+            ))
+        );
+    }
+}
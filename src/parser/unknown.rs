@@ -0,0 +1,92 @@
+use nom::{bytes, combinator, sequence};
+
+use super::{
+    utils::{line_ending, parse_multiline_text, parse_until_line_ending},
+    IResult, MetaDataField, MultiLineField,
+};
+
+const KNOWN_METADATA_KEYS: &[&str] = &[
+    "AUTHOR",
+    "TITLE",
+    "BASENAME",
+    "STATUS",
+    "ALLOW COMMENTS",
+    "ALLOW PINGS",
+    "CONVERT BREAKS",
+    "PRIMARY CATEGORY",
+    "CATEGORY",
+    "DATE",
+    "TAGS",
+    "NO ENTRY",
+    "IMAGE",
+    "UNIQUE URL",
+];
+
+const KNOWN_MULTILINE_KEYS: &[&str] = &[
+    "BODY",
+    "EXTENDED BODY",
+    "EXCERPT",
+    "KEYWORDS",
+    "COMMENT",
+    "PING",
+    "PINGED URLS",
+];
+
+fn parse_key(input: &str) -> IResult<'_, &str> {
+    bytes::complete::take_till1(|c| c == ':' || c == '\n' || c == '\r')(input)
+}
+
+// Known keys with a malformed value must still be reported as errors,
+// not swallowed as unknown fields.
+fn parse_unknown_key<'a>(
+    known_keys: &'static [&'static str],
+) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    combinator::verify(parse_key, move |key: &str| !known_keys.contains(&key))
+}
+
+// <KEY>: <text>\n
+pub fn parse_unknown_metadata_data(input: &str) -> IResult<'_, MetaDataField<'_>> {
+    let (input, key) = parse_unknown_key(KNOWN_METADATA_KEYS)(input)?;
+    let (input, _) = bytes::complete::tag(": ")(input)?;
+    let (input, value) = parse_until_line_ending(input)?;
+
+    Ok((input, MetaDataField::Unknown(key, value)))
+}
+
+// <KEY>:\n<text>\n-----\n
+pub fn parse_unknown_multiline_data(input: &str) -> IResult<'_, MultiLineField<'_>> {
+    let (input, key) = sequence::terminated(
+        parse_unknown_key(KNOWN_MULTILINE_KEYS),
+        sequence::terminated(bytes::complete::tag(":"), line_ending),
+    )(input)?;
+    let (input, text) = parse_multiline_text(input)?;
+
+    Ok((input, MultiLineField::Unknown(key, text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unknown_metadata_data() {
+        assert_eq!(
+            parse_unknown_metadata_data("CUSTOM FIELD: https://example.com/\n"),
+            Ok((
+                "",
+                MetaDataField::Unknown("CUSTOM FIELD", "https://example.com/")
+            ))
+        );
+        assert!(parse_unknown_metadata_data("STATUS: Bogus\n").is_err());
+        assert!(parse_unknown_metadata_data("BODY:\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_multiline_data() {
+        assert_eq!(
+            parse_unknown_multiline_data("CUSTOM NOTES:\nFoo Bar\n-----\n"),
+            Ok(("", MultiLineField::Unknown("CUSTOM NOTES", "Foo Bar")))
+        );
+        assert!(parse_unknown_multiline_data("BODY:\nFoo\n-----\n").is_err());
+    }
+}
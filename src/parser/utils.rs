@@ -3,19 +3,40 @@ use nom::{
     combinator::{self, map},
     multi,
     sequence::{self, preceded},
-    IResult,
 };
 
-pub fn parse_until_line_ending(input: &str) -> IResult<&str, &str> {
+use super::{IResult, ParseError, ParseErrorKind};
+use crate::options::{DateFormat, DateFormats};
+
+// \n, \r\n or a bare \r
+pub fn line_ending(input: &str) -> IResult<'_, &str> {
+    branch::alt((
+        bytes::complete::tag("\r\n"),
+        bytes::complete::tag("\n"),
+        bytes::complete::tag("\r"),
+    ))(input)
+}
+
+pub fn strip_line_ending_suffix(text: &str) -> Option<&str> {
+    text.strip_suffix("\r\n")
+        .or_else(|| text.strip_suffix('\n'))
+        .or_else(|| text.strip_suffix('\r'))
+}
+
+pub fn parse_until_line_ending(input: &str) -> IResult<'_, &str> {
     sequence::terminated(
-        bytes::complete::take_until("\n"),
-        character::complete::newline,
+        bytes::complete::take_till(|c| c == '\n' || c == '\r'),
+        line_ending,
     )(input)
 }
 
-pub fn take_n_digits<'a>(n: usize) -> impl FnMut(&'a str) -> IResult<&'a str, u32> {
+pub fn take_n_digits<'a>(n: usize) -> impl FnMut(&'a str) -> IResult<'a, u32> {
+    take_m_n_digits(n, n)
+}
+
+pub fn take_m_n_digits<'a>(m: usize, n: usize) -> impl FnMut(&'a str) -> IResult<'a, u32> {
     map(
-        multi::many_m_n(n, n, character::complete::satisfy(|c| c.is_digit(10))),
+        multi::many_m_n(m, n, character::complete::satisfy(|c| c.is_ascii_digit())),
         |digits: Vec<char>| {
             let num_str: String = digits.into_iter().collect();
             num_str
@@ -25,10 +46,10 @@ pub fn take_n_digits<'a>(n: usize) -> impl FnMut(&'a str) -> IResult<&'a str, u3
     )
 }
 
-pub fn parse_num_bool_flag(input: &str) -> IResult<&str, bool> {
+pub fn parse_num_bool_flag(input: &str) -> IResult<'_, bool> {
     let flag_parser = sequence::terminated(
         branch::alt((bytes::complete::tag("0"), bytes::complete::tag("1"))),
-        character::complete::newline,
+        line_ending,
     );
     let flag_to_bool = |status: &str| match status.to_ascii_lowercase().as_str() {
         "0" => false,
@@ -39,50 +60,155 @@ pub fn parse_num_bool_flag(input: &str) -> IResult<&str, bool> {
     combinator::map(flag_parser, flag_to_bool)(input)
 }
 
-pub fn parse_date_value(input: &str) -> IResult<&str, time::PrimitiveDateTime> {
-    let am_pm_parser = preceded(
-        bytes::complete::tag(" "),
-        branch::alt((bytes::complete::tag("AM"), bytes::complete::tag("PM"))),
-    );
-    let (input, (month, _, day, _, year, _, hour, _, minutes, _, seconds, am_pm)) =
+// A date and, if the input gave one, its offset from UTC.
+pub type DateValue = (time::PrimitiveDateTime, Option<time::UtcOffset>);
+
+fn invalid_date(input: &str) -> nom::Err<ParseError<'_>> {
+    nom::Err::Error(ParseError::new(input, ParseErrorKind::InvalidDate))
+}
+
+// MM/DD/YYYY hh:MM:SS [AM|PM], or with `relaxed` M/D/YYYY h:MM:SS [am|pm]
+fn parse_us_date_value<'a>(
+    relaxed: bool,
+) -> impl FnMut(&'a str) -> IResult<'a, time::PrimitiveDateTime> {
+    let min_digits = if relaxed { 1 } else { 2 };
+    move |input| {
+        let am_pm_parser = preceded(
+            bytes::complete::tag(" "),
+            branch::alt((
+                combinator::verify(bytes::complete::tag_no_case("AM"), |am: &str| {
+                    relaxed || am == "AM"
+                }),
+                combinator::verify(bytes::complete::tag_no_case("PM"), |pm: &str| {
+                    relaxed || pm == "PM"
+                }),
+            )),
+        );
+        let date_start = input;
+        let (input, (month, _, day, _, year, _, hour, _, minutes, _, seconds, am_pm)) =
+            sequence::tuple((
+                take_m_n_digits(min_digits, 2),
+                bytes::complete::tag("/"),
+                take_m_n_digits(min_digits, 2),
+                bytes::complete::tag("/"),
+                take_n_digits(4),
+                bytes::complete::tag(" "),
+                take_m_n_digits(min_digits, 2),
+                bytes::complete::tag(":"),
+                take_n_digits(2),
+                bytes::complete::tag(":"),
+                take_n_digits(2),
+                combinator::opt(am_pm_parser),
+            ))(input)?;
+
+        let month = time::Month::try_from(month as u8).map_err(|_| invalid_date(date_start))?;
+        let date = time::Date::from_calendar_date(year as i32, month, day as u8)
+            .map_err(|_| invalid_date(date_start))?;
+        let hour = match am_pm.map(str::to_ascii_uppercase).as_deref() {
+            // 12-hour clock: 12 AM is midnight and 12 PM is noon.
+            Some(_) if hour > 12 => return Err(invalid_date(date_start)),
+            Some("AM") => hour % 12,
+            Some("PM") => hour % 12 + 12,
+            Some(_) => unreachable!(),
+            None => hour,
+        };
+        let time = time::Time::from_hms(hour as u8, minutes as u8, seconds as u8)
+            .map_err(|_| invalid_date(date_start))?;
+
+        Ok((input, time::PrimitiveDateTime::new(date, time)))
+    }
+}
+
+// Z or ±hh:MM
+fn parse_utc_offset(input: &str) -> IResult<'_, time::UtcOffset> {
+    if let Ok((input, _)) = bytes::complete::tag::<_, _, ParseError>("Z")(input) {
+        return Ok((input, time::UtcOffset::UTC));
+    }
+
+    let offset_start = input;
+    let (input, (sign, hours, _, minutes)) = sequence::tuple((
+        character::complete::one_of("+-"),
+        take_n_digits(2),
+        bytes::complete::tag(":"),
+        take_n_digits(2),
+    ))(input)?;
+    let sign = if sign == '-' { -1 } else { 1 };
+    let offset = time::UtcOffset::from_hms(sign * hours as i8, sign * minutes as i8, 0)
+        .map_err(|_| invalid_date(offset_start))?;
+
+    Ok((input, offset))
+}
+
+// YYYY-MM-DDThh:MM:SS[Z|±hh:MM]
+fn parse_iso8601_date_value(input: &str) -> IResult<'_, DateValue> {
+    let date_start = input;
+    let (input, (year, _, month, _, day, _, hour, _, minutes, _, seconds, offset)) =
         sequence::tuple((
+            take_n_digits(4),
+            bytes::complete::tag("-"),
             take_n_digits(2),
-            bytes::complete::tag("/"),
+            bytes::complete::tag("-"),
             take_n_digits(2),
-            bytes::complete::tag("/"),
-            take_n_digits(4),
-            bytes::complete::tag(" "),
+            branch::alt((bytes::complete::tag("T"), bytes::complete::tag(" "))),
             take_n_digits(2),
             bytes::complete::tag(":"),
             take_n_digits(2),
             bytes::complete::tag(":"),
             take_n_digits(2),
-            combinator::opt(am_pm_parser),
+            combinator::opt(parse_utc_offset),
         ))(input)?;
 
-    let date =
-        time::Date::from_calendar_date(year as i32, (month as u8).try_into().unwrap(), day as u8)
-            .expect("Invalid date");
-    let time =
-        time::Time::from_hms(hour as u8, minutes as u8, seconds as u8).expect("Invalid time");
-    if let Some(am_pm) = am_pm {
-        let time = match am_pm {
-            "AM" => time,
-            "PM" => time + time::Duration::hours(12),
-            _ => unreachable!(),
-        };
-        Ok((input, time::PrimitiveDateTime::new(date, time)))
-    } else {
-        Ok((input, time::PrimitiveDateTime::new(date, time)))
+    let month = time::Month::try_from(month as u8).map_err(|_| invalid_date(date_start))?;
+    let date = time::Date::from_calendar_date(year as i32, month, day as u8)
+        .map_err(|_| invalid_date(date_start))?;
+    let time = time::Time::from_hms(hour as u8, minutes as u8, seconds as u8)
+        .map_err(|_| invalid_date(date_start))?;
+
+    Ok((input, (time::PrimitiveDateTime::new(date, time), offset)))
+}
+
+fn parse_date_value(format: DateFormat, input: &str) -> IResult<'_, DateValue> {
+    match format {
+        DateFormat::MovableType => {
+            combinator::map(parse_us_date_value(false), |date| (date, None))(input)
+        }
+        DateFormat::Relaxed => {
+            combinator::map(parse_us_date_value(true), |date| (date, None))(input)
+        }
+        DateFormat::Iso8601 => parse_iso8601_date_value(input),
     }
 }
 
-pub fn parse_multiline_text(input: &str) -> IResult<&str, &str> {
-    let multiline_data_separator: &str = "\n-----\n";
-    sequence::terminated(
-        bytes::complete::take_until(multiline_data_separator),
-        bytes::complete::take(multiline_data_separator.len()),
-    )(input)
+// Once a DATE: tag has matched, a malformed value is reported as is
+// instead of letting the caller backtrack into another field parser.
+pub fn parse_date_line<'a>(formats: DateFormats) -> impl FnMut(&'a str) -> IResult<'a, DateValue> {
+    move |input| {
+        DateFormat::ALL
+            .into_iter()
+            .filter(|format| formats.contains(*format))
+            .find_map(|format| {
+                sequence::terminated(|input| parse_date_value(format, input), line_ending)(input)
+                    .ok()
+            })
+            .ok_or_else(|| nom::Err::Failure(ParseError::new(input, ParseErrorKind::InvalidDate)))
+    }
+}
+
+// Text up to a line consisting of "-----". The line ending before the
+// separator line belongs to the separator, not to the text.
+pub fn parse_multiline_text(input: &str) -> IResult<'_, &str> {
+    let multiline_data_separator: &str = "-----";
+    input
+        .match_indices(multiline_data_separator)
+        .find_map(|(i, separator)| {
+            let text = match &input[..i] {
+                "" => "",
+                before => strip_line_ending_suffix(before)?,
+            };
+            let (rest, _) = line_ending(&input[i + separator.len()..]).ok()?;
+            Some((rest, text))
+        })
+        .ok_or_else(|| nom::Err::Failure(ParseError::new(input, ParseErrorKind::MissingSeparator)))
 }
 
 #[cfg(test)]
@@ -97,6 +223,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_until_line_ending_crlf() {
+        assert_eq!(
+            parse_until_line_ending("Foo Bar\r\nBaz Qux"),
+            Ok(("Baz Qux", "Foo Bar"))
+        );
+        assert_eq!(
+            parse_until_line_ending("Foo Bar\rBaz Qux"),
+            Ok(("Baz Qux", "Foo Bar"))
+        );
+    }
+
+    #[test]
+    fn test_parse_multiline_text() {
+        assert_eq!(
+            parse_multiline_text("Foo\n------\nBar\n-----\n--------"),
+            Ok(("--------", "Foo\n------\nBar"))
+        );
+        assert_eq!(
+            parse_multiline_text("Foo\r\nBar\r\n-----\r\n--------"),
+            Ok(("--------", "Foo\r\nBar"))
+        );
+        assert_eq!(
+            parse_multiline_text("Foo\rBar\r-----\r--------"),
+            Ok(("--------", "Foo\rBar"))
+        );
+        assert_eq!(parse_multiline_text("-----\n"), Ok(("", "")));
+    }
+
     #[test]
     fn test_take_n_digits() {
         assert_eq!(take_n_digits(2)("12"), Ok(("", 12)));
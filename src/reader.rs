@@ -0,0 +1,214 @@
+use std::io::{self, BufRead};
+
+use crate::error::MTIFError;
+use crate::span::LineIndex;
+use crate::{MTIFParser, OwnedMTIFEntry};
+
+const SECTION_SEPARATOR: &[u8] = b"-----";
+const ENTRY_TERMINATOR: &[u8] = b"--------";
+
+/// Reads entries one at a time from a buffered reader.
+///
+/// The input is split on `--------` lines that follow a `-----` line, so
+/// only one entry is held in memory at a time; a `--------` line elsewhere,
+/// as in a `BODY`, does not end the entry. Lines may end in `\n`, `\r\n`
+/// or a bare `\r`. A parse error is reported for the offending entry and
+/// reading continues with the next one; an error from the underlying reader
+/// ends the iteration.
+pub struct MTIFReader<R> {
+    reader: R,
+    parser: MTIFParser,
+    buffer: Vec<u8>,
+    lines_read: usize,
+    entries_read: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> MTIFReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, MTIFParser::new())
+    }
+
+    pub fn with_parser(reader: R, parser: MTIFParser) -> Self {
+        MTIFReader {
+            reader,
+            parser,
+            buffer: Vec::new(),
+            lines_read: 0,
+            entries_read: 0,
+            finished: false,
+        }
+    }
+
+    // Fills the buffer up to and including the next entry terminator line,
+    // returning the number of lines read.
+    fn read_entry_chunk(&mut self) -> io::Result<usize> {
+        self.buffer.clear();
+        let mut lines = 0;
+        let mut after_separator = false;
+        loop {
+            let line_start = self.buffer.len();
+            if read_line(&mut self.reader, &mut self.buffer)? == 0 {
+                return Ok(lines);
+            }
+            lines += 1;
+            let line = &self.buffer[line_start..];
+            let line = line.strip_suffix(b"\n").unwrap_or(line);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if after_separator && line == ENTRY_TERMINATOR {
+                return Ok(lines);
+            }
+            after_separator = line == SECTION_SEPARATOR;
+        }
+    }
+
+    fn parse_entry_chunk(&self) -> Result<OwnedMTIFEntry, MTIFError> {
+        let chunk = std::str::from_utf8(&self.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if chunk.trim().is_empty() {
+            return Err(MTIFError::TrailingInput(
+                LineIndex::new(chunk).error_location(chunk, 0),
+            ));
+        }
+
+        let mut entries = self.parser.parse(chunk)?;
+        debug_assert_eq!(entries.len(), 1);
+        Ok(entries.remove(0).into_owned())
+    }
+}
+
+// Appends one line, including its line ending, to `buffer`. Unlike
+// `BufRead::read_line`, a bare \r also ends a line.
+fn read_line<R: BufRead>(reader: &mut R, buffer: &mut Vec<u8>) -> io::Result<usize> {
+    let start = buffer.len();
+    loop {
+        let available = reader.fill_buf()?;
+        let Some(i) = available.iter().position(|&b| b == b'\n' || b == b'\r') else {
+            if available.is_empty() {
+                break;
+            }
+            let len = available.len();
+            buffer.extend_from_slice(available);
+            reader.consume(len);
+            continue;
+        };
+
+        let ending = available[i];
+        buffer.extend_from_slice(&available[..=i]);
+        reader.consume(i + 1);
+        if ending == b'\r' && reader.fill_buf()?.first() == Some(&b'\n') {
+            buffer.push(b'\n');
+            reader.consume(1);
+        }
+        break;
+    }
+
+    Ok(buffer.len() - start)
+}
+
+impl<R: BufRead> Iterator for MTIFReader<R> {
+    type Item = Result<OwnedMTIFEntry, MTIFError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let lines = match self.read_entry_chunk() {
+            Ok(lines) => lines,
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(e.into()));
+            }
+        };
+        if self.buffer.is_empty() {
+            self.finished = true;
+            return None;
+        }
+
+        let result = self
+            .parse_entry_chunk()
+            .map_err(|e| e.offset_by(self.lines_read, self.entries_read));
+        self.lines_read += lines;
+        self.entries_read += 1;
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorLocation;
+
+    #[test]
+    fn test_read_entries() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let expected: Vec<_> = MTIFParser::new()
+            .parse(&contents)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.into_owned())
+            .collect();
+
+        let file = std::fs::File::open("./example/example.txt").unwrap();
+        let entries: Result<Vec<_>, _> = MTIFReader::new(std::io::BufReader::new(file)).collect();
+        assert_eq!(entries.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_read_entries_with_error() {
+        let contents = "DATE: 01/31/2002 03:31:05\n-----\n--------\n\
+                        TITLE: broken\n\
+                        DATE: 13/31/2002 03:31:05\n-----\n--------\n\
+                        DATE: 01/31/2002 03:31:05\n-----\n--------\n";
+        let results: Vec<_> = MTIFReader::new(contents.as_bytes()).collect();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &MTIFError::InvalidDate(ErrorLocation {
+                line: 5,
+                column: 7,
+                entry: 1,
+                source_line: "DATE: 13/31/2002 03:31:05".to_string(),
+            })
+        );
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn test_read_entries_terminator_in_body() {
+        let contents =
+            "DATE: 01/31/2002 03:31:05\n-----\nBODY:\nfoo\n--------\nbar\n-----\n--------\n\
+                        DATE: 01/31/2002 03:31:05\n-----\n--------\n";
+        let expected: Vec<_> = MTIFParser::new()
+            .parse(contents)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.into_owned())
+            .collect();
+
+        let entries: Result<Vec<_>, _> = MTIFReader::new(contents.as_bytes()).collect();
+        assert_eq!(entries.unwrap(), expected);
+        assert_eq!(expected.len(), 2);
+        assert_eq!(expected[0].body.as_deref(), Some("foo\n--------\nbar"));
+    }
+
+    #[test]
+    fn test_read_entries_line_endings() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let expected: Vec<_> = MTIFReader::new(contents.as_bytes())
+            .map(|e| e.unwrap().metadata)
+            .collect();
+
+        for line_ending in ["\r\n", "\r"] {
+            let converted = contents.replace('\n', line_ending);
+            let metadata: Vec<_> = MTIFReader::new(converted.as_bytes())
+                .map(|e| e.unwrap().metadata)
+                .collect();
+            assert_eq!(metadata, expected);
+        }
+    }
+}
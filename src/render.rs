@@ -0,0 +1,238 @@
+//! HTML rendering of entry text according to its `CONVERT BREAKS` mode.
+
+use std::fmt;
+
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::model::ConvertBreaks;
+use crate::MTIFEntry;
+
+/// A `CONVERT BREAKS` mode [`render`] cannot turn into HTML, as written
+/// in the input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnsupportedFormat(pub String);
+
+impl fmt::Display for UnsupportedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported text format: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedFormat {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RenderedEntry {
+    pub body: Option<String>,
+    pub extended_body: Option<String>,
+    pub excerpt: Option<String>,
+}
+
+/// Renders `text` as HTML.
+///
+/// - `None`, `RichText` and `Wysiwyg` are already HTML and returned as
+///   they are, as is `HatenaNotation`, since Hatena Blog exports the
+///   rendered HTML.
+/// - `Convert` wraps paragraphs in `<p>` and turns line breaks into
+///   `<br />`, as Movable Type does. So does `Default`, the usual blog
+///   default.
+/// - `Markdown` and `MarkdownWithSmartypants` go through a CommonMark
+///   renderer, the latter with smart quotes, dashes and ellipses.
+/// - `Textile2` and `Other` filters are not supported.
+pub fn render(text: &str, convert_breaks: ConvertBreaks<'_>) -> Result<String, UnsupportedFormat> {
+    match convert_breaks {
+        ConvertBreaks::None
+        | ConvertBreaks::RichText
+        | ConvertBreaks::Wysiwyg
+        | ConvertBreaks::HatenaNotation => Ok(text.to_string()),
+        ConvertBreaks::Convert | ConvertBreaks::Default => Ok(convert_breaks_to_html(text)),
+        ConvertBreaks::Markdown => Ok(markdown_to_html(text, Options::empty())),
+        ConvertBreaks::MarkdownWithSmartypants => {
+            Ok(markdown_to_html(text, Options::ENABLE_SMART_PUNCTUATION))
+        }
+        ConvertBreaks::Textile2 | ConvertBreaks::Other(_) => {
+            Err(UnsupportedFormat(convert_breaks.as_str().to_string()))
+        }
+    }
+}
+
+/// Renders the body, extended body and excerpt of `entry`. Entries without
+/// `CONVERT BREAKS` are rendered as `Convert`, Movable Type's default.
+pub fn render_entry(entry: &MTIFEntry<'_>) -> Result<RenderedEntry, UnsupportedFormat> {
+    let convert_breaks = entry
+        .metadata
+        .convert_breaks
+        .unwrap_or(ConvertBreaks::Convert);
+    let render = |text: Option<&str>| text.map(|text| render(text, convert_breaks)).transpose();
+
+    Ok(RenderedEntry {
+        body: render(entry.body)?,
+        extended_body: render(entry.extended_body)?,
+        excerpt: render(entry.excerpt)?,
+    })
+}
+
+// Paragraphs that start with one of these tags are left alone.
+const BLOCK_TAGS: [&str; 22] = [
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "table",
+    "ol",
+    "dl",
+    "ul",
+    "menu",
+    "dir",
+    "p",
+    "pre",
+    "center",
+    "form",
+    "fieldset",
+    "select",
+    "blockquote",
+    "address",
+    "div",
+    "hr",
+];
+
+// A port of `MT::Util::html_text_transform`.
+fn convert_breaks_to_html(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut paragraphs: Vec<&str> = text.split("\n\n").collect();
+    while paragraphs
+        .last()
+        .is_some_and(|paragraph| paragraph.is_empty())
+    {
+        paragraphs.pop();
+    }
+
+    paragraphs
+        .into_iter()
+        .map(|paragraph| {
+            if starts_with_block_tag(paragraph) {
+                paragraph.to_string()
+            } else {
+                format!("<p>{}</p>", paragraph.replace('\n', "<br />\n"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn starts_with_block_tag(paragraph: &str) -> bool {
+    paragraph
+        .strip_prefix('<')
+        .map(|tag| tag.strip_prefix('/').unwrap_or(tag))
+        .is_some_and(|tag| BLOCK_TAGS.iter().any(|name| tag.starts_with(name)))
+}
+
+fn markdown_to_html(text: &str, options: Options) -> String {
+    let mut output = String::with_capacity(text.len() * 3 / 2);
+    html::push_html(&mut output, Parser::new_ext(text, options));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MTIFParser;
+
+    #[test]
+    fn test_render_convert() {
+        assert_eq!(
+            render(
+                "First line\r\nsecond line\r\n\r\n<blockquote>Quote\n</blockquote>\n\nLast\n\n",
+                ConvertBreaks::Convert
+            ),
+            Ok("<p>First line<br />\nsecond line</p>\n\n\
+                <blockquote>Quote\n</blockquote>\n\n\
+                <p>Last</p>"
+                .to_string())
+        );
+        assert_eq!(
+            render("a\rb\r\rc", ConvertBreaks::Convert),
+            Ok("<p>a<br />\nb</p>\n\n<p>c</p>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let text = "A \"quoted\" *word* -- isn't it...";
+        assert_eq!(
+            render(text, ConvertBreaks::Markdown),
+            Ok("<p>A \"quoted\" <em>word</em> -- isn't it...</p>\n".to_string())
+        );
+        assert_eq!(
+            render(text, ConvertBreaks::MarkdownWithSmartypants),
+            Ok("<p>A “quoted” <em>word</em> – isn’t it…</p>\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_pass_through() {
+        let text = "<b>bold</b>\n\nnext";
+        assert_eq!(render(text, ConvertBreaks::None), Ok(text.to_string()));
+        assert_eq!(render(text, ConvertBreaks::RichText), Ok(text.to_string()));
+        assert_eq!(
+            render(text, ConvertBreaks::Textile2),
+            Err(UnsupportedFormat("textile_2".to_string()))
+        );
+        assert_eq!(
+            render(text, ConvertBreaks::Other("wiki"))
+                .unwrap_err()
+                .to_string(),
+            "unsupported text format: wiki"
+        );
+    }
+
+    #[test]
+    fn test_render_entry() {
+        let contents = "CONVERT BREAKS: markdown\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        BODY:\n\
+                        # Heading\n\
+                        -----\n\
+                        EXCERPT:\n\
+                        *short*\n\
+                        -----\n\
+                        --------\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        BODY:\n\
+                        Line\n\
+                        break\n\
+                        -----\n\
+                        --------\n";
+        let entries = MTIFParser::new().parse(contents).unwrap();
+
+        assert_eq!(
+            render_entry(&entries[0]),
+            Ok(RenderedEntry {
+                body: Some("<h1>Heading</h1>\n".to_string()),
+                extended_body: None,
+                excerpt: Some("<p><em>short</em></p>\n".to_string()),
+            })
+        );
+        assert_eq!(
+            render_entry(&entries[1]).unwrap().body,
+            Some("<p>Line<br />\nbreak</p>".to_string())
+        );
+
+        let contents = contents.replace(
+            "DATE: 01/31/2002 03:31:05 PM\n-----\nBODY:\nLine",
+            "CONVERT BREAKS: __default__\nDATE: 01/31/2002 03:31:05 PM\n-----\nBODY:\nLine",
+        );
+        let entries = MTIFParser::new().parse(&contents).unwrap();
+        assert_eq!(
+            entries[1].metadata.convert_breaks,
+            Some(ConvertBreaks::Default)
+        );
+        assert_eq!(
+            render_entry(&entries[1]).unwrap().body,
+            Some("<p>Line<br />\nbreak</p>".to_string())
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Source positions of parsed entries and fields, as returned by
+//! [`MTIFParser::parse_with_spans`](crate::MTIFParser::parse_with_spans).
+
+use std::ops::Range;
+
+use nom::Offset;
+
+use crate::error::ErrorLocation;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    /// 0-based byte offset into the input.
+    pub offset: usize,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+}
+
+/// A region of the input, excluding its final line ending.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldSpan<'a> {
+    /// The field name as written in the input, e.g. `TITLE` or `COMMENT`.
+    pub key: &'a str,
+    pub span: Span,
+}
+
+/// Where an entry and each of its fields are in the input. Fields are
+/// listed in input order, so the n-th `COMMENT` span belongs to the n-th
+/// comment of the entry.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EntrySpans<'a> {
+    /// From the first metadata line through the `--------` terminator.
+    pub entry: Span,
+    /// One span per metadata line.
+    pub metadata: Vec<FieldSpan<'a>>,
+    /// One span per multi-line block, from its header through `-----`.
+    pub multiline: Vec<FieldSpan<'a>>,
+}
+
+impl<'a> EntrySpans<'a> {
+    pub fn metadata_spans<'s>(&'s self, key: &'s str) -> impl Iterator<Item = Span> + 's {
+        find_spans(&self.metadata, key)
+    }
+
+    pub fn multiline_spans<'s>(&'s self, key: &'s str) -> impl Iterator<Item = Span> + 's {
+        find_spans(&self.multiline, key)
+    }
+}
+
+fn find_spans<'s>(fields: &'s [FieldSpan<'_>], key: &'s str) -> impl Iterator<Item = Span> + 's {
+    fields
+        .iter()
+        .filter(move |field| field.key == key)
+        .map(|field| field.span)
+}
+
+/// Maps slices of `source` to positions. Line starts are computed once, so
+/// looking up many spans of a large input stays cheap.
+pub(crate) struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        let bytes = source.as_bytes();
+        let mut line_starts = vec![0];
+        for (i, &b) in bytes.iter().enumerate() {
+            // \r\n counts as a single line ending.
+            if b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n')) {
+                line_starts.push(i + 1);
+            }
+        }
+
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        Position {
+            offset,
+            line: line + 1,
+            column: self.source[line_start..offset].chars().count() + 1,
+        }
+    }
+
+    /// `at` must be a suffix of the indexed source.
+    pub(crate) fn error_location(&self, at: &str, entry: usize) -> ErrorLocation {
+        let position = self.position(self.source.offset(at));
+        let line_start = self.line_starts[position.line - 1];
+        let line_end = self.source[position.offset..]
+            .find(['\n', '\r'])
+            .map_or(self.source.len(), |i| position.offset + i);
+
+        ErrorLocation {
+            line: position.line,
+            column: position.column,
+            entry,
+            source_line: self.source[line_start..line_end].to_string(),
+        }
+    }
+
+    /// `slice` must be a slice of the indexed source.
+    pub(crate) fn span(&self, slice: &str) -> Span {
+        let start = self.source.offset(slice);
+        let end = start + slice.trim_end_matches(['\r', '\n']).len();
+
+        Span {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+
+    pub(crate) fn field_span(&self, slice: &'a str) -> FieldSpan<'a> {
+        FieldSpan {
+            key: slice.split(':').next().unwrap_or_default(),
+            span: self.span(slice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index_span() {
+        let source = "TITLE: foo\r\nBODY:\rbär\nbaz\n-----\n";
+        let index = LineIndex::new(source);
+
+        assert_eq!(
+            index.field_span(&source[12..]),
+            FieldSpan {
+                key: "BODY",
+                span: Span {
+                    start: Position {
+                        offset: 12,
+                        line: 2,
+                        column: 1,
+                    },
+                    end: Position {
+                        offset: source.len() - 1,
+                        line: 5,
+                        column: 6,
+                    },
+                },
+            }
+        );
+        assert_eq!(
+            index.span(&source[18..22]).end,
+            Position {
+                offset: 22,
+                line: 3,
+                column: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_line_index_error_location() {
+        let source = "TITLE: foo\nDATE: bar\n";
+        assert_eq!(
+            LineIndex::new(source).error_location(&source[17..], 0),
+            ErrorLocation {
+                line: 2,
+                column: 7,
+                entry: 0,
+                source_line: "DATE: bar".to_string(),
+            }
+        );
+
+        let source = "TITLE: foo\r\nBODY: bär\rDATE: bar\r\n";
+        let index = LineIndex::new(source);
+        assert_eq!(
+            index.error_location(&source[29..], 1),
+            ErrorLocation {
+                line: 3,
+                column: 7,
+                entry: 1,
+                source_line: "DATE: bar".to_string(),
+            }
+        );
+        assert_eq!(
+            index.error_location(&source[21..], 1),
+            ErrorLocation {
+                line: 2,
+                column: 9,
+                entry: 1,
+                source_line: "BODY: bär".to_string(),
+            }
+        );
+        assert_eq!(
+            index.error_location(&source[source.len()..], 1),
+            ErrorLocation {
+                line: 4,
+                column: 1,
+                entry: 1,
+                source_line: String::new(),
+            }
+        );
+    }
+}
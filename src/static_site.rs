@@ -0,0 +1,480 @@
+//! Export to Markdown files with front matter for static site generators.
+//!
+//! Each entry becomes one file named `YYYY-MM-DD-<basename>.md`. The body
+//! and extended body are written as they are, joined by a "more" marker, so
+//! HTML bodies stay HTML; Markdown processors pass it through.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::model::Status;
+use crate::MTIFEntry;
+
+/// The static site generator the files are written for. It decides which
+/// front matter keys are used and the defaults for
+/// [`StaticSiteExporter::front_matter`] and
+/// [`StaticSiteExporter::more_marker`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SiteGenerator {
+    Hugo,
+    Jekyll,
+    Zola,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FrontMatter {
+    /// Between `---` lines.
+    Yaml,
+    /// Between `+++` lines.
+    Toml,
+}
+
+enum Value<'a> {
+    String(&'a str),
+    Bool(bool),
+    Date(time::OffsetDateTime),
+    List(&'a [&'a str]),
+}
+
+pub struct StaticSiteExporter {
+    generator: SiteGenerator,
+    front_matter: FrontMatter,
+    more_marker: String,
+}
+
+impl StaticSiteExporter {
+    pub fn new(generator: SiteGenerator) -> Self {
+        let (front_matter, more_marker) = match generator {
+            SiteGenerator::Hugo | SiteGenerator::Jekyll => (FrontMatter::Yaml, "<!--more-->"),
+            SiteGenerator::Zola => (FrontMatter::Toml, "<!-- more -->"),
+        };
+        StaticSiteExporter {
+            generator,
+            front_matter,
+            more_marker: more_marker.to_string(),
+        }
+    }
+
+    pub fn front_matter(mut self, front_matter: FrontMatter) -> Self {
+        self.front_matter = front_matter;
+        self
+    }
+
+    /// The line written between the body and the extended body.
+    pub fn more_marker(mut self, more_marker: impl Into<String>) -> Self {
+        self.more_marker = more_marker.into();
+        self
+    }
+
+    /// The file name for `entry`, from its date and `BASENAME` (or `TITLE`
+    /// when there is none).
+    pub fn path(&self, entry: &MTIFEntry<'_>) -> PathBuf {
+        let date = entry.metadata.date;
+        let slug = slug(entry);
+        PathBuf::from(format!(
+            "{:04}-{:02}-{:02}-{}.md",
+            date.year(),
+            u8::from(date.month()),
+            date.day(),
+            if slug.is_empty() { "entry" } else { &slug }
+        ))
+    }
+
+    /// Writes one file per entry into `dir`, creating it if needed, and
+    /// returns the paths written. Entries that would share a file name get
+    /// a numeric suffix.
+    pub fn export(
+        &self,
+        dir: impl AsRef<Path>,
+        entries: &[MTIFEntry<'_>],
+    ) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let mut used = HashSet::new();
+        let mut paths = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut path = dir.join(self.path(entry));
+            let stem = path.file_stem().unwrap_or_default().to_os_string();
+            for n in 2.. {
+                if used.insert(path.clone()) {
+                    break;
+                }
+                let mut file_name = stem.clone();
+                file_name.push(format!("-{}.md", n));
+                path.set_file_name(file_name);
+            }
+
+            let mut file = io::BufWriter::new(fs::File::create(&path)?);
+            self.write_entry(&mut file, entry)?;
+            file.flush()?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+
+    pub fn write_entry<W: io::Write>(
+        &self,
+        mut writer: W,
+        entry: &MTIFEntry<'_>,
+    ) -> io::Result<()> {
+        let delimiter = match self.front_matter {
+            FrontMatter::Yaml => "---",
+            FrontMatter::Toml => "+++",
+        };
+        writeln!(writer, "{}", delimiter)?;
+        self.write_front_matter(&mut writer, entry)?;
+        writeln!(writer, "{}", delimiter)?;
+        writeln!(writer)?;
+
+        if let Some(body) = entry.body {
+            writeln!(writer, "{}", body)?;
+        }
+        if let Some(extended_body) = entry.extended_body {
+            writeln!(writer)?;
+            writeln!(writer, "{}", self.more_marker)?;
+            writeln!(writer)?;
+            writeln!(writer, "{}", extended_body)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_front_matter<W: io::Write>(
+        &self,
+        mut writer: W,
+        entry: &MTIFEntry<'_>,
+    ) -> io::Result<()> {
+        let metadata = &entry.metadata;
+        let slug = slug(entry);
+        let draft = metadata.status == Some(Status::Draft);
+        let authors: Vec<&str> = metadata.author.into_iter().collect();
+        let mut categories: Vec<&str> = metadata
+            .primary_category
+            .filter(|primary_category| !metadata.category.contains(primary_category))
+            .into_iter()
+            .collect();
+        categories.extend(&metadata.category);
+
+        // Zola rejects unknown top-level keys, so taxonomies and anything
+        // else go in their own tables.
+        let zola = self.generator == SiteGenerator::Zola;
+        let mut fields = vec![];
+        let mut taxonomies = vec![];
+        let mut extra = vec![];
+
+        if let Some(title) = metadata.title {
+            fields.push(("title", Value::String(title)));
+        }
+        fields.push(("date", Value::Date(metadata.date_with_offset())));
+        if !slug.is_empty() {
+            fields.push(("slug", Value::String(&slug)));
+        }
+        match metadata.author {
+            Some(_) if zola => fields.push(("authors", Value::List(&authors))),
+            Some(author) => fields.push(("author", Value::String(author))),
+            None => {}
+        }
+        let taxonomy_fields = if zola { &mut taxonomies } else { &mut fields };
+        if !categories.is_empty() {
+            taxonomy_fields.push(("categories", Value::List(&categories)));
+        }
+        if !metadata.tags.is_empty() {
+            taxonomy_fields.push(("tags", Value::List(&metadata.tags)));
+        }
+        match self.generator {
+            SiteGenerator::Hugo | SiteGenerator::Zola => fields.push(("draft", Value::Bool(draft))),
+            // Jekyll has no draft flag in front matter; unpublished posts
+            // are skipped instead.
+            SiteGenerator::Jekyll => fields.push(("published", Value::Bool(!draft))),
+        }
+        if let Some(image) = metadata.image {
+            let image_fields = if zola { &mut extra } else { &mut fields };
+            image_fields.push(("image", Value::String(image)));
+        }
+
+        self.write_fields(&mut writer, &fields)?;
+        self.write_table(&mut writer, "taxonomies", &taxonomies)?;
+        self.write_table(&mut writer, "extra", &extra)
+    }
+
+    fn write_table<W: io::Write>(
+        &self,
+        mut writer: W,
+        name: &str,
+        fields: &[(&str, Value<'_>)],
+    ) -> io::Result<()> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+        match self.front_matter {
+            FrontMatter::Yaml => {
+                writeln!(writer, "{}:", name)?;
+                for (key, value) in fields {
+                    write!(writer, "  ")?;
+                    self.write_field(&mut writer, key, value)?;
+                }
+                Ok(())
+            }
+            FrontMatter::Toml => {
+                writeln!(writer, "[{}]", name)?;
+                self.write_fields(writer, fields)
+            }
+        }
+    }
+
+    fn write_fields<W: io::Write>(
+        &self,
+        mut writer: W,
+        fields: &[(&str, Value<'_>)],
+    ) -> io::Result<()> {
+        for (key, value) in fields {
+            self.write_field(&mut writer, key, value)?;
+        }
+        Ok(())
+    }
+
+    // Strings are always quoted and lists written inline, which reads the
+    // same in YAML and TOML.
+    fn write_field<W: io::Write>(
+        &self,
+        mut writer: W,
+        key: &str,
+        value: &Value<'_>,
+    ) -> io::Result<()> {
+        match self.front_matter {
+            FrontMatter::Yaml => write!(writer, "{}: ", key)?,
+            FrontMatter::Toml => write!(writer, "{} = ", key)?,
+        }
+        match value {
+            Value::String(s) => write!(writer, "{}", quoted(s))?,
+            Value::Bool(b) => write!(writer, "{}", b)?,
+            Value::Date(date) => write_date_value(&mut writer, *date)?,
+            Value::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| quoted(item)).collect();
+                write!(writer, "[{}]", items.join(", "))?;
+            }
+        }
+        writeln!(writer)
+    }
+}
+
+// YYYY-MM-DDThh:MM:SS±hh:MM, valid in both YAML and TOML
+fn write_date_value<W: io::Write>(mut writer: W, date: time::OffsetDateTime) -> io::Result<()> {
+    let (hours, minutes, _) = date.offset().as_hms();
+    write!(
+        writer,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second(),
+        if date.offset().is_negative() {
+            '-'
+        } else {
+            '+'
+        },
+        hours.unsigned_abs(),
+        minutes.unsigned_abs()
+    )
+}
+
+// A double-quoted string with the escapes YAML and TOML have in common.
+fn quoted(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub(crate) fn slug(entry: &MTIFEntry<'_>) -> String {
+    let name = entry
+        .metadata
+        .basename
+        .or(entry.metadata.title)
+        .unwrap_or_default();
+    slugify(name, &['_'])
+}
+
+// Lowercases `name` and joins its runs of alphanumeric characters and
+// `keep` with single dashes.
+pub(crate) fn slugify(name: &str, keep: &[char]) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || keep.contains(&c) {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let len = slug.trim_end_matches('-').len();
+    slug.truncate(len);
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MTIFParser;
+
+    const CONTENTS: &str = "TITLE: A \"quoted\" title\n\
+                            BASENAME: a_quoted_title\n\
+                            AUTHOR: Foo Bar\n\
+                            STATUS: Draft\n\
+                            PRIMARY CATEGORY: Media\n\
+                            CATEGORY: News\n\
+                            DATE: 01/31/2002 03:31:05 PM\n\
+                            TAGS: \"Movable Type\",foo\n\
+                            IMAGE: https://example.com/image.png\n\
+                            -----\n\
+                            BODY:\n\
+                            This is the body.\n\
+                            -----\n\
+                            EXTENDED BODY:\n\
+                            Here is some more text.\n\
+                            -----\n\
+                            --------\n";
+
+    fn write_to_string(exporter: &StaticSiteExporter, entry: &MTIFEntry<'_>) -> String {
+        let mut output = Vec::new();
+        exporter.write_entry(&mut output, entry).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_entry_hugo() {
+        let parser = MTIFParser::with_options(
+            crate::ParseOptions::new().timezone(time::macros::offset!(+9)),
+        );
+        let entries = parser.parse(CONTENTS).unwrap();
+        let exporter = StaticSiteExporter::new(SiteGenerator::Hugo);
+
+        assert_eq!(
+            exporter.path(&entries[0]),
+            PathBuf::from("2002-01-31-a_quoted_title.md")
+        );
+        assert_eq!(
+            write_to_string(&exporter, &entries[0]),
+            "---\n\
+             title: \"A \\\"quoted\\\" title\"\n\
+             date: 2002-01-31T15:31:05+09:00\n\
+             slug: \"a_quoted_title\"\n\
+             author: \"Foo Bar\"\n\
+             categories: [\"Media\", \"News\"]\n\
+             tags: [\"Movable Type\", \"foo\"]\n\
+             draft: true\n\
+             image: \"https://example.com/image.png\"\n\
+             ---\n\
+             \n\
+             This is the body.\n\
+             \n\
+             <!--more-->\n\
+             \n\
+             Here is some more text.\n"
+        );
+    }
+
+    #[test]
+    fn test_write_entry_jekyll() {
+        let entries = MTIFParser::new().parse(CONTENTS).unwrap();
+        let exporter =
+            StaticSiteExporter::new(SiteGenerator::Jekyll).more_marker("<!-- excerpt -->");
+
+        let output = write_to_string(&exporter, &entries[0]);
+        assert!(output.contains("\npublished: false\n"));
+        assert!(!output.contains("draft"));
+        assert!(output.contains("\n<!-- excerpt -->\n"));
+    }
+
+    #[test]
+    fn test_write_entry_zola() {
+        let entries = MTIFParser::new().parse(CONTENTS).unwrap();
+
+        assert_eq!(
+            write_to_string(&StaticSiteExporter::new(SiteGenerator::Zola), &entries[0]),
+            "+++\n\
+             title = \"A \\\"quoted\\\" title\"\n\
+             date = 2002-01-31T15:31:05+00:00\n\
+             slug = \"a_quoted_title\"\n\
+             authors = [\"Foo Bar\"]\n\
+             draft = true\n\
+             [taxonomies]\n\
+             categories = [\"Media\", \"News\"]\n\
+             tags = [\"Movable Type\", \"foo\"]\n\
+             [extra]\n\
+             image = \"https://example.com/image.png\"\n\
+             +++\n\
+             \n\
+             This is the body.\n\
+             \n\
+             <!-- more -->\n\
+             \n\
+             Here is some more text.\n"
+        );
+
+        let output = write_to_string(
+            &StaticSiteExporter::new(SiteGenerator::Zola).front_matter(FrontMatter::Yaml),
+            &entries[0],
+        );
+        assert!(output.starts_with("---\ntitle: "));
+        assert!(output.contains("\ntaxonomies:\n  categories: [\"Media\", \"News\"]\n"));
+    }
+
+    #[test]
+    fn test_export() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = MTIFParser::new().parse(&contents).unwrap();
+        entries.push(entries[0].clone());
+
+        let dir = std::env::temp_dir().join(format!("mtif-static-site-{}", std::process::id()));
+        let paths = StaticSiteExporter::new(SiteGenerator::Hugo)
+            .export(&dir, &entries)
+            .unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "2002-01-31-a-dummy-title.md",
+                "2002-01-31-here-is-a-new-entry.md",
+                "2002-01-31-a-dummy-title-2.md",
+            ]
+        );
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .starts_with("---\ntitle: \"Here is a new entry\"\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quoted() {
+        assert_eq!(quoted("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Movable Type", &[]), "movable-type");
+        assert_eq!(slugify("  C++ & Rust! ", &[]), "c-rust");
+        assert_eq!(slugify("日本語", &[]), "日本語");
+        assert_eq!(slugify("a_quoted title", &[]), "a-quoted-title");
+        assert_eq!(slugify("a_quoted title", &['_']), "a_quoted-title");
+    }
+}
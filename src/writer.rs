@@ -0,0 +1,611 @@
+use std::io;
+
+use crate::model::{Comment, CommentStatus, MetaData, Ping, Status};
+use crate::MTIFEntry;
+
+#[derive(Default)]
+pub struct MTIFWriter {
+    timezone: Option<time::UtcOffset>,
+}
+
+impl MTIFWriter {
+    pub fn new() -> Self {
+        MTIFWriter { timezone: None }
+    }
+
+    /// Converts dates to `offset` before writing them. By default dates are
+    /// written as they are, in their own `utc_offset`.
+    pub fn timezone(mut self, offset: time::UtcOffset) -> Self {
+        self.timezone = Some(offset);
+        self
+    }
+
+    fn local_date(
+        &self,
+        date: time::PrimitiveDateTime,
+        utc_offset: time::UtcOffset,
+    ) -> time::PrimitiveDateTime {
+        match self.timezone {
+            Some(timezone) => {
+                let date = date.assume_offset(utc_offset).to_offset(timezone);
+                time::PrimitiveDateTime::new(date.date(), date.time())
+            }
+            None => date,
+        }
+    }
+
+    pub fn write<W: io::Write>(&self, mut writer: W, entries: &[MTIFEntry<'_>]) -> io::Result<()> {
+        for entry in entries {
+            self.write_entry(&mut writer, entry)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_entry<W: io::Write>(
+        &self,
+        mut writer: W,
+        entry: &MTIFEntry<'_>,
+    ) -> io::Result<()> {
+        self.write_metadata(&mut writer, &entry.metadata)?;
+        writeln!(writer, "-----")?;
+
+        if let Some(body) = entry.body {
+            write_multiline_field(&mut writer, "BODY", body)?;
+        }
+        if let Some(extended_body) = entry.extended_body {
+            write_multiline_field(&mut writer, "EXTENDED BODY", extended_body)?;
+        }
+        if let Some(excerpt) = entry.excerpt {
+            write_multiline_field(&mut writer, "EXCERPT", excerpt)?;
+        }
+        if let Some(keywords) = entry.keywords {
+            write_multiline_field(&mut writer, "KEYWORDS", keywords)?;
+        }
+        for comment in &entry.comments {
+            self.write_comment(&mut writer, comment)?;
+        }
+        for ping in &entry.pings {
+            self.write_ping(&mut writer, ping)?;
+        }
+        if !entry.pinged_urls.is_empty() {
+            write_multiline_field(&mut writer, "PINGED URLS", &entry.pinged_urls.join("\n"))?;
+        }
+        for (name, text) in &entry.extra_sections {
+            write_multiline_field(&mut writer, name, text)?;
+        }
+
+        writeln!(writer, "--------")
+    }
+
+    fn write_metadata<W: io::Write>(
+        &self,
+        mut writer: W,
+        metadata: &MetaData<'_>,
+    ) -> io::Result<()> {
+        if let Some(author) = metadata.author {
+            write_field(&mut writer, "AUTHOR", author)?;
+        }
+        if let Some(title) = metadata.title {
+            write_field(&mut writer, "TITLE", title)?;
+        }
+        if let Some(basename) = metadata.basename {
+            write_field(&mut writer, "BASENAME", basename)?;
+        }
+        if let Some(status) = metadata.status {
+            writeln!(writer, "STATUS: {}", status_value(status))?;
+        }
+        if let Some(allow_comments) = metadata.allow_comments {
+            writeln!(writer, "ALLOW COMMENTS: {}", u8::from(allow_comments))?;
+        }
+        if let Some(allow_pings) = metadata.allow_pings {
+            writeln!(writer, "ALLOW PINGS: {}", u8::from(allow_pings))?;
+        }
+        if let Some(convert_breaks) = metadata.convert_breaks {
+            if convert_breaks.as_str().is_empty() {
+                return Err(invalid_input("CONVERT BREAKS is empty".to_string()));
+            }
+            write_field(&mut writer, "CONVERT BREAKS", convert_breaks.as_str())?;
+        }
+        if let Some(primary_category) = metadata.primary_category {
+            write_field(&mut writer, "PRIMARY CATEGORY", primary_category)?;
+        }
+        for category in &metadata.category {
+            write_field(&mut writer, "CATEGORY", category)?;
+        }
+        write!(writer, "DATE: ")?;
+        write_date_value(
+            &mut writer,
+            self.local_date(metadata.date, metadata.utc_offset),
+        )?;
+        writeln!(writer)?;
+        if metadata.no_entry {
+            writeln!(writer, "NO ENTRY: 1")?;
+        }
+        if !metadata.tags.is_empty() {
+            let tags = metadata
+                .tags
+                .iter()
+                .map(|tag| tag_value(tag))
+                .collect::<io::Result<Vec<_>>>()?;
+            writeln!(writer, "TAGS: {}", tags.join(","))?;
+        }
+        if let Some(image) = metadata.image {
+            write_field(&mut writer, "IMAGE", image)?;
+        }
+        if let Some(unique_url) = metadata.unique_url {
+            write_field(&mut writer, "UNIQUE URL", unique_url)?;
+        }
+        for (key, value) in &metadata.extra {
+            write_field(&mut writer, key, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_comment<W: io::Write>(&self, mut writer: W, comment: &Comment<'_>) -> io::Result<()> {
+        writeln!(writer, "COMMENT:")?;
+        if let Some(author) = comment.author {
+            write_field(&mut writer, "AUTHOR", author)?;
+        }
+        if let Some(email) = comment.email {
+            write_field(&mut writer, "EMAIL", email)?;
+        }
+        if let Some(url) = comment.url {
+            write_field(&mut writer, "URL", url)?;
+        }
+        if let Some(ip) = comment.ip {
+            write_field(&mut writer, "IP", ip)?;
+        }
+        if let Some(date) = comment.date {
+            write!(writer, "DATE: ")?;
+            write_date_value(&mut writer, self.local_date(date, comment.utc_offset))?;
+            writeln!(writer)?;
+        }
+        if let Some(status) = comment.status {
+            writeln!(writer, "STATUS: {}", comment_status_value(status))?;
+        }
+        write_field_text(writer, &COMMENT_FIELDS, comment.text)
+    }
+
+    fn write_ping<W: io::Write>(&self, mut writer: W, ping: &Ping<'_>) -> io::Result<()> {
+        writeln!(writer, "PING:")?;
+        if let Some(title) = ping.title {
+            write_field(&mut writer, "TITLE", title)?;
+        }
+        if let Some(url) = ping.url {
+            write_field(&mut writer, "URL", url)?;
+        }
+        if let Some(ip) = ping.ip {
+            write_field(&mut writer, "IP", ip)?;
+        }
+        if let Some(blog_name) = ping.blog_name {
+            write_field(&mut writer, "BLOG NAME", blog_name)?;
+        }
+        if let Some(date) = ping.date {
+            write!(writer, "DATE: ")?;
+            write_date_value(&mut writer, self.local_date(date, ping.utc_offset))?;
+            writeln!(writer)?;
+        }
+        if let Some(status) = ping.status {
+            writeln!(writer, "STATUS: {}", comment_status_value(status))?;
+        }
+        write_field_text(writer, &PING_FIELDS, ping.text)
+    }
+}
+
+// A line ending would end the value early.
+fn write_field<W: io::Write>(mut writer: W, name: &str, value: &str) -> io::Result<()> {
+    if value.contains(['\n', '\r']) {
+        return Err(invalid_input(format!(
+            "{} {:?} contains a line ending",
+            name, value
+        )));
+    }
+    writeln!(writer, "{}: {}", name, value)
+}
+
+const COMMENT_FIELDS: [&str; 6] = ["AUTHOR: ", "EMAIL: ", "URL: ", "IP: ", "DATE: ", "STATUS: "];
+const PING_FIELDS: [&str; 6] = [
+    "TITLE: ",
+    "URL: ",
+    "IP: ",
+    "BLOG NAME: ",
+    "DATE: ",
+    "STATUS: ",
+];
+
+// Comment and ping text follows their fields, so a first line that starts
+// like one of `fields` would be read back as that field.
+fn write_field_text<W: io::Write>(writer: W, fields: &[&str], text: &str) -> io::Result<()> {
+    let first_line = text.split(['\n', '\r']).next().unwrap_or_default();
+    if fields.iter().any(|field| first_line.starts_with(field)) {
+        return Err(invalid_input(format!(
+            "text {:?} starts with a field",
+            text
+        )));
+    }
+    write_multiline_text(writer, text)
+}
+
+fn write_multiline_field<W: io::Write>(mut writer: W, name: &str, text: &str) -> io::Result<()> {
+    writeln!(writer, "{}:", name)?;
+    write_multiline_text(writer, text)
+}
+
+// A `-----` line would end the text early, and MTIF has no way to escape it.
+fn write_multiline_text<W: io::Write>(mut writer: W, text: &str) -> io::Result<()> {
+    if text.split(['\n', '\r']).any(|line| line == "-----") {
+        return Err(invalid_input(format!(
+            "text {:?} contains a ----- line",
+            text
+        )));
+    }
+    writeln!(writer, "{}", text)?;
+    writeln!(writer, "-----")
+}
+
+// MM/DD/YYYY hh:MM:SS AM|PM
+fn write_date_value<W: io::Write>(mut writer: W, date: time::PrimitiveDateTime) -> io::Result<()> {
+    let (hour, am_pm) = match date.hour() {
+        0 => (12, "AM"),
+        hour @ 1..=11 => (hour, "AM"),
+        12 => (12, "PM"),
+        hour => (hour - 12, "PM"),
+    };
+    write!(
+        writer,
+        "{:02}/{:02}/{:04} {:02}:{:02}:{:02} {}",
+        u8::from(date.month()),
+        date.day(),
+        date.year(),
+        hour,
+        date.minute(),
+        date.second(),
+        am_pm
+    )
+}
+
+// Tags with whitespace or a comma are quoted. A quoted tag cannot contain a
+// quote, an unquoted one cannot start with one, and no tag can contain a
+// line ending.
+fn tag_value(tag: &str) -> io::Result<String> {
+    let quoted = tag.contains(|c: char| c.is_whitespace() || c == ',');
+    let representable = if quoted {
+        !tag.contains('"')
+    } else {
+        !tag.starts_with('"')
+    };
+    if !representable || tag.contains(['\n', '\r']) {
+        return Err(invalid_input(format!("tag {:?} cannot be written", tag)));
+    }
+
+    if quoted {
+        Ok(format!("\"{}\"", tag))
+    } else {
+        Ok(tag.to_string())
+    }
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn status_value(status: Status) -> &'static str {
+    match status {
+        Status::Draft => "Draft",
+        Status::Publish => "Publish",
+        Status::Future => "Future",
+    }
+}
+
+fn comment_status_value(status: CommentStatus) -> &'static str {
+    match status {
+        CommentStatus::Approved => "approved",
+        CommentStatus::Pending => "pending",
+        CommentStatus::Spam => "spam",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ConvertBreaks;
+    use crate::MTIFParser;
+
+    fn write_to_string(entries: &[MTIFEntry<'_>]) -> String {
+        let mut output = Vec::new();
+        MTIFWriter::new().write(&mut output, entries).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_entry() {
+        let entry = MTIFEntry {
+            metadata: MetaData {
+                author: Some("Foo Bar"),
+                title: Some("A dummy title"),
+                basename: None,
+                status: Some(Status::Publish),
+                allow_comments: Some(true),
+                allow_pings: None,
+                convert_breaks: Some(ConvertBreaks::MarkdownWithSmartypants),
+                primary_category: None,
+                category: vec!["News", "Media"],
+                date: time::macros::datetime!(2002-01-31 00:31:05),
+                utc_offset: time::UtcOffset::UTC,
+                no_entry: false,
+                tags: vec!["Movable Type", "foo"],
+                image: None,
+                unique_url: Some("https://example.com/2002/01/a-dummy-title.html"),
+                extra: vec![("CUSTOM FIELD", "https://example.com/")],
+            },
+            body: Some("This is the body."),
+            extended_body: None,
+            excerpt: None,
+            keywords: None,
+            comments: vec![Comment {
+                author: Some("Foo"),
+                email: None,
+                url: None,
+                ip: None,
+                date: Some(time::macros::datetime!(2002-01-31 12:47:06)),
+                utc_offset: time::UtcOffset::UTC,
+                status: Some(CommentStatus::Approved),
+                text: "This is\nthe body of this comment.",
+            }],
+            pings: vec![],
+            pinged_urls: vec!["https://example.com/a", "https://example.com/b"],
+            extra_sections: vec![("CUSTOM NOTES", "https://example.com/ping")],
+        };
+
+        assert_eq!(
+            write_to_string(&[entry]),
+            "AUTHOR: Foo Bar\n\
+             TITLE: A dummy title\n\
+             STATUS: Publish\n\
+             ALLOW COMMENTS: 1\n\
+             CONVERT BREAKS: markdown_with_smartypants\n\
+             CATEGORY: News\n\
+             CATEGORY: Media\n\
+             DATE: 01/31/2002 12:31:05 AM\n\
+             TAGS: \"Movable Type\",foo\n\
+             UNIQUE URL: https://example.com/2002/01/a-dummy-title.html\n\
+             CUSTOM FIELD: https://example.com/\n\
+             -----\n\
+             BODY:\n\
+             This is the body.\n\
+             -----\n\
+             COMMENT:\n\
+             AUTHOR: Foo\n\
+             DATE: 01/31/2002 12:47:06 PM\n\
+             STATUS: approved\n\
+             This is\n\
+             the body of this comment.\n\
+             -----\n\
+             PINGED URLS:\n\
+             https://example.com/a\n\
+             https://example.com/b\n\
+             -----\n\
+             CUSTOM NOTES:\n\
+             https://example.com/ping\n\
+             -----\n\
+             --------\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip_unknown_fields() {
+        let parser = MTIFParser::with_options(
+            crate::ParseOptions::new().unknown_fields(crate::UnknownFields::Keep),
+        );
+        let contents = "CUSTOM FIELD: https://example.com/\n\
+                        DATE: 01/31/2002 03:31:05 PM\n\
+                        CUSTOM FIELD: foo\n\
+                        -----\n\
+                        CUSTOM NOTES:\n\
+                        https://example.com/ping\n\
+                        -----\n\
+                        BODY:\n\
+                        body\n\
+                        -----\n\
+                        --------\n";
+        let entries = parser.parse(contents).unwrap();
+        assert_eq!(
+            entries[0].metadata.extra,
+            vec![
+                ("CUSTOM FIELD", "https://example.com/"),
+                ("CUSTOM FIELD", "foo")
+            ]
+        );
+        assert_eq!(
+            entries[0].extra_sections,
+            vec![("CUSTOM NOTES", "https://example.com/ping")]
+        );
+
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_round_trip_convert_breaks() {
+        let parser = MTIFParser::new();
+        for value in ["0", "markdown", "__default__", "wiki", "textile"] {
+            let contents = format!(
+                "CONVERT BREAKS: {}\nDATE: 01/31/2002 03:31:05 PM\n-----\n--------\n",
+                value
+            );
+            let entries = parser.parse(&contents).unwrap();
+            assert_eq!(
+                entries[0].metadata.convert_breaks.map(|c| c.as_str()),
+                Some(value)
+            );
+
+            let mut output = Vec::new();
+            MTIFWriter::new().write(&mut output, &entries).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), contents);
+        }
+    }
+
+    #[test]
+    fn test_write_timezone() {
+        let tokyo = time::macros::offset!(+9);
+        let parser = MTIFParser::with_options(crate::ParseOptions::new().timezone(tokyo));
+        let contents = "DATE: 01/31/2002 03:31:05 PM\n\
+                        -----\n\
+                        COMMENT:\n\
+                        DATE: 02/01/2002 08:00:00 AM\n\
+                        comment\n\
+                        -----\n\
+                        --------\n";
+        let entries = parser.parse(contents).unwrap();
+        assert_eq!(
+            entries[0].metadata.date_utc(),
+            time::macros::datetime!(2002-01-31 06:31:05 UTC)
+        );
+        assert_eq!(
+            entries[0].comments[0].date_utc(),
+            Some(time::macros::datetime!(2002-01-31 23:00:00 UTC))
+        );
+
+        let mut output = Vec::new();
+        MTIFWriter::new()
+            .timezone(time::UtcOffset::UTC)
+            .write(&mut output, &entries)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "DATE: 01/31/2002 06:31:05 AM\n\
+             -----\n\
+             COMMENT:\n\
+             DATE: 01/31/2002 11:00:00 PM\n\
+             comment\n\
+             -----\n\
+             --------\n"
+        );
+        assert_eq!(write_to_string(&entries), contents);
+    }
+
+    #[test]
+    fn test_round_trip_tags() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = parser.parse(&contents).unwrap();
+
+        entries[0].metadata.tags = vec!["Movable Type", "5\"", "a,b", "日本 語"];
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+
+        for tag in ["say \"hi\"", "\"open", "two\nlines"] {
+            entries[0].metadata.tags = vec![tag];
+            let error = MTIFWriter::new()
+                .write(&mut Vec::new(), &entries)
+                .unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_separator_in_text() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = parser.parse(&contents).unwrap();
+
+        entries[0].body = Some("foo\n------\n--------\n-----bar");
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+
+        entries[0].body = Some("foo\n-----\nbar");
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        entries[0].body = None;
+        entries[0].comments[0].text = "-----";
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_round_trip_line_ending_in_value() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = parser.parse(&contents).unwrap();
+
+        entries[0].metadata.title = Some("line1 line2");
+        entries[0].metadata.category = vec!["a", "b"];
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+
+        entries[0].metadata.title = Some("line1\nline2");
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        entries[0].metadata.title = None;
+        entries[0].metadata.category = vec!["a\rb"];
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        entries[0].metadata.category = vec![];
+        entries[0].comments[0].email = Some("foo@example.com\n");
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_round_trip_field_in_text() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = parser.parse(&contents).unwrap();
+
+        entries[0].comments[0].text = "rest\nEMAIL: not an email";
+        entries[0].pings[0].text = "AUTHOR: not a ping field";
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+
+        entries[0].comments[0].text = "EMAIL: not an email\nrest";
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        entries[0].comments[0].text = "rest";
+        entries[0].pings[0].text = "BLOG NAME: not a blog name";
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_round_trip_empty_convert_breaks() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let mut entries = parser.parse(&contents).unwrap();
+
+        entries[0].metadata.convert_breaks = Some(ConvertBreaks::Other("wiki"));
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+
+        entries[0].metadata.convert_breaks = Some(ConvertBreaks::Other(""));
+        let error = MTIFWriter::new()
+            .write(&mut Vec::new(), &entries)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let parser = MTIFParser::new();
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let entries = parser.parse(&contents).unwrap();
+
+        let written = write_to_string(&entries);
+        assert_eq!(parser.parse(&written).unwrap(), entries);
+    }
+}
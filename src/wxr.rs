@@ -0,0 +1,340 @@
+//! Export to and import from WordPress eXtended RSS (WXR), the format of
+//! the WordPress exporter and importer.
+//!
+//! Each entry becomes a post. `KEYWORDS`, `PINGED URLS` and unknown fields
+//! have no WXR counterpart and are not exported.
+
+use std::io;
+
+use quick_xml::events::{BytesDecl, Event};
+use quick_xml::Writer;
+
+use crate::model::{Comment, CommentStatus, Ping, Status};
+use crate::static_site::slugify;
+use crate::xml::{format_error, rfc2822, write_cdata, write_cdata_events, write_text};
+use crate::MTIFEntry;
+
+mod reader;
+
+pub use reader::{from_wxr, WXRError};
+
+const NAMESPACES: [(&str, &str); 5] = [
+    ("xmlns:excerpt", "http://wordpress.org/export/1.2/excerpt/"),
+    ("xmlns:content", "http://purl.org/rss/1.0/modules/content/"),
+    ("xmlns:wfw", "http://wellformedweb.org/CommentAPI/"),
+    ("xmlns:dc", "http://purl.org/dc/elements/1.1/"),
+    ("xmlns:wp", "http://wordpress.org/export/1.2/"),
+];
+
+#[derive(Default)]
+pub struct WXRWriter {
+    title: String,
+    link: String,
+    description: String,
+}
+
+impl WXRWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The blog title, written to the channel.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// The blog URL, written to the channel as its link and base URL.
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.link = link.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn write<W: io::Write>(&self, writer: W, entries: &[MTIFEntry<'_>]) -> io::Result<()> {
+        let mut writer = Writer::new_with_indent(writer, b' ', 2);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        writer
+            .create_element("rss")
+            .with_attribute(("version", "2.0"))
+            .with_attributes(NAMESPACES)
+            .write_inner_content(|writer| {
+                writer
+                    .create_element("channel")
+                    .write_inner_content(|writer| self.write_channel(writer, entries))?;
+                Ok(())
+            })?;
+        writeln!(writer.get_mut())
+    }
+
+    fn write_channel<W: io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+        entries: &[MTIFEntry<'_>],
+    ) -> io::Result<()> {
+        write_text(writer, "title", &self.title)?;
+        write_text(writer, "link", &self.link)?;
+        write_text(writer, "description", &self.description)?;
+        write_text(writer, "wp:wxr_version", "1.2")?;
+        write_text(writer, "wp:base_site_url", &self.link)?;
+        write_text(writer, "wp:base_blog_url", &self.link)?;
+
+        let mut authors: Vec<&str> = vec![];
+        for author in entries.iter().filter_map(|entry| entry.metadata.author) {
+            if !authors.contains(&author) {
+                authors.push(author);
+            }
+        }
+        for (id, author) in authors.iter().enumerate() {
+            writer
+                .create_element("wp:author")
+                .write_inner_content(|writer| {
+                    write_text(writer, "wp:author_id", &(id + 1).to_string())?;
+                    write_cdata(writer, "wp:author_login", author)?;
+                    write_cdata(writer, "wp:author_display_name", author)
+                })?;
+        }
+
+        let mut comment_id = 0;
+        for (index, entry) in entries.iter().enumerate() {
+            writer
+                .create_element("item")
+                .write_inner_content(|writer| {
+                    write_item(writer, index + 1, &mut comment_id, entry)
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_item<W: io::Write>(
+    writer: &mut Writer<W>,
+    post_id: usize,
+    comment_id: &mut usize,
+    entry: &MTIFEntry<'_>,
+) -> io::Result<()> {
+    let metadata = &entry.metadata;
+
+    write_text(writer, "title", metadata.title.unwrap_or_default())?;
+    write_text(writer, "pubDate", &rfc2822(metadata.date_with_offset())?)?;
+    write_cdata(writer, "dc:creator", metadata.author.unwrap_or_default())?;
+    write_text(writer, "description", "")?;
+    write_cdata(writer, "content:encoded", &content(entry))?;
+    write_cdata(writer, "excerpt:encoded", entry.excerpt.unwrap_or_default())?;
+    write_text(writer, "wp:post_id", &post_id.to_string())?;
+    write_text(writer, "wp:post_date", &date_value(metadata.date)?)?;
+    write_text(
+        writer,
+        "wp:post_date_gmt",
+        &date_value(utc_date(metadata.date_utc()))?,
+    )?;
+    write_text(
+        writer,
+        "wp:comment_status",
+        open_value(metadata.allow_comments),
+    )?;
+    write_text(writer, "wp:ping_status", open_value(metadata.allow_pings))?;
+    write_text(
+        writer,
+        "wp:post_name",
+        metadata.basename.unwrap_or_default(),
+    )?;
+    write_text(writer, "wp:status", status_value(metadata.status))?;
+    write_text(writer, "wp:post_parent", "0")?;
+    write_text(writer, "wp:menu_order", "0")?;
+    write_text(writer, "wp:post_type", "post")?;
+    write_text(writer, "wp:post_password", "")?;
+    write_text(writer, "wp:is_sticky", "0")?;
+    let primary_category = metadata
+        .primary_category
+        .filter(|primary_category| !metadata.category.contains(primary_category));
+    for category in primary_category.iter().chain(&metadata.category) {
+        write_category(writer, "category", category)?;
+    }
+    for tag in &metadata.tags {
+        write_category(writer, "post_tag", tag)?;
+    }
+    for comment in &entry.comments {
+        *comment_id += 1;
+        write_comment(writer, *comment_id, comment)?;
+    }
+    for ping in &entry.pings {
+        *comment_id += 1;
+        write_ping(writer, *comment_id, ping)?;
+    }
+
+    Ok(())
+}
+
+fn write_comment<W: io::Write>(
+    writer: &mut Writer<W>,
+    comment_id: usize,
+    comment: &Comment<'_>,
+) -> io::Result<()> {
+    writer
+        .create_element("wp:comment")
+        .write_inner_content(|writer| {
+            write_text(writer, "wp:comment_id", &comment_id.to_string())?;
+            write_cdata(
+                writer,
+                "wp:comment_author",
+                comment.author.unwrap_or_default(),
+            )?;
+            write_text(
+                writer,
+                "wp:comment_author_email",
+                comment.email.unwrap_or_default(),
+            )?;
+            write_text(
+                writer,
+                "wp:comment_author_url",
+                comment.url.unwrap_or_default(),
+            )?;
+            write_text(
+                writer,
+                "wp:comment_author_IP",
+                comment.ip.unwrap_or_default(),
+            )?;
+            write_comment_dates(writer, comment.date_with_offset())?;
+            write_cdata(writer, "wp:comment_content", comment.text)?;
+            write_text(
+                writer,
+                "wp:comment_approved",
+                approved_value(comment.status),
+            )?;
+            write_text(writer, "wp:comment_type", "")?;
+            write_text(writer, "wp:comment_parent", "0")
+        })?;
+    Ok(())
+}
+
+fn write_ping<W: io::Write>(
+    writer: &mut Writer<W>,
+    comment_id: usize,
+    ping: &Ping<'_>,
+) -> io::Result<()> {
+    writer
+        .create_element("wp:comment")
+        .write_inner_content(|writer| {
+            write_text(writer, "wp:comment_id", &comment_id.to_string())?;
+            write_cdata(
+                writer,
+                "wp:comment_author",
+                ping.blog_name.or(ping.title).unwrap_or_default(),
+            )?;
+            write_text(writer, "wp:comment_author_email", "")?;
+            write_text(
+                writer,
+                "wp:comment_author_url",
+                ping.url.unwrap_or_default(),
+            )?;
+            write_text(writer, "wp:comment_author_IP", ping.ip.unwrap_or_default())?;
+            write_comment_dates(writer, ping.date_with_offset())?;
+            write_cdata(writer, "wp:comment_content", ping.text)?;
+            write_text(writer, "wp:comment_approved", approved_value(ping.status))?;
+            write_text(writer, "wp:comment_type", "pingback")?;
+            write_text(writer, "wp:comment_parent", "0")
+        })?;
+    Ok(())
+}
+
+fn write_comment_dates<W: io::Write>(
+    writer: &mut Writer<W>,
+    date: Option<time::OffsetDateTime>,
+) -> io::Result<()> {
+    let Some(date) = date else {
+        return Ok(());
+    };
+    let local = time::PrimitiveDateTime::new(date.date(), date.time());
+    write_text(writer, "wp:comment_date", &date_value(local)?)?;
+    write_text(
+        writer,
+        "wp:comment_date_gmt",
+        &date_value(utc_date(date.to_offset(time::UtcOffset::UTC)))?,
+    )
+}
+
+fn write_category<W: io::Write>(
+    writer: &mut Writer<W>,
+    domain: &str,
+    name: &str,
+) -> io::Result<()> {
+    let nicename = slugify(name, &[]);
+    writer
+        .create_element("category")
+        .with_attribute(("domain", domain))
+        .with_attribute(("nicename", nicename.as_str()))
+        .write_inner_content(|writer| write_cdata_events(writer, name))?;
+    Ok(())
+}
+
+// WordPress shows the part after <!--more--> only on the post page, like
+// MT's extended body.
+fn content(entry: &MTIFEntry<'_>) -> String {
+    let body = entry.body.unwrap_or_default();
+    match entry.extended_body {
+        Some(extended_body) => format!("{}\n<!--more-->\n{}", body, extended_body),
+        None => body.to_string(),
+    }
+}
+
+fn utc_date(date: time::OffsetDateTime) -> time::PrimitiveDateTime {
+    time::PrimitiveDateTime::new(date.date(), date.time())
+}
+
+// YYYY-MM-DD hh:MM:SS
+fn date_value(date: time::PrimitiveDateTime) -> io::Result<String> {
+    date.format(time::macros::format_description!(
+        "[year]-[month]-[day] [hour]:[minute]:[second]"
+    ))
+    .map_err(format_error)
+}
+
+fn open_value(allowed: Option<bool>) -> &'static str {
+    match allowed {
+        Some(false) => "closed",
+        Some(true) | None => "open",
+    }
+}
+
+fn status_value(status: Option<Status>) -> &'static str {
+    match status {
+        Some(Status::Draft) => "draft",
+        Some(Status::Future) => "future",
+        Some(Status::Publish) | None => "publish",
+    }
+}
+
+// A comment without a status is shown, as in MT.
+fn approved_value(status: Option<CommentStatus>) -> &'static str {
+    match status {
+        Some(CommentStatus::Pending) => "0",
+        Some(CommentStatus::Spam) => "spam",
+        Some(CommentStatus::Approved) | None => "1",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MTIFParser;
+
+    #[test]
+    fn test_write_wxr() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let entries = MTIFParser::new().parse(&contents).unwrap();
+
+        let mut output = Vec::new();
+        WXRWriter::new()
+            .title("Example")
+            .link("https://example.com")
+            .write(&mut output, &entries)
+            .unwrap();
+        insta::assert_snapshot!(String::from_utf8(output).unwrap());
+    }
+}
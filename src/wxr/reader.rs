@@ -0,0 +1,500 @@
+use std::fmt;
+use std::io;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::model::{
+    OwnedComment, OwnedConvertBreaks, OwnedMTIFEntry, OwnedMetaData, OwnedPing, Status,
+};
+
+#[derive(Debug)]
+pub enum WXRError {
+    /// The input is not well-formed XML, or reading it failed.
+    Xml(quick_xml::Error),
+    /// An item has no `wp:post_date`, or one that is not
+    /// `YYYY-MM-DD hh:MM:SS`.
+    InvalidDate {
+        /// 0-based index of the `item` element, counting every item.
+        item: usize,
+        value: String,
+    },
+}
+
+impl fmt::Display for WXRError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WXRError::Xml(e) => write!(f, "invalid WXR: {}", e),
+            WXRError::InvalidDate { item, value } => {
+                write!(f, "invalid wp:post_date in item {}: {:?}", item, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WXRError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WXRError::Xml(e) => Some(e),
+            WXRError::InvalidDate { .. } => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for WXRError {
+    fn from(e: quick_xml::Error) -> Self {
+        WXRError::Xml(e)
+    }
+}
+
+impl From<quick_xml::encoding::EncodingError> for WXRError {
+    fn from(e: quick_xml::encoding::EncodingError) -> Self {
+        WXRError::Xml(e.into())
+    }
+}
+
+#[derive(Default)]
+struct RawItem {
+    title: Option<String>,
+    creator: Option<String>,
+    content: Option<String>,
+    excerpt: Option<String>,
+    post_date: Option<String>,
+    post_date_gmt: Option<String>,
+    comment_status: Option<String>,
+    ping_status: Option<String>,
+    post_name: Option<String>,
+    status: Option<String>,
+    post_type: Option<String>,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    comments: Vec<RawComment>,
+}
+
+#[derive(Default)]
+struct RawComment {
+    author: Option<String>,
+    email: Option<String>,
+    url: Option<String>,
+    ip: Option<String>,
+    date: Option<String>,
+    date_gmt: Option<String>,
+    content: Option<String>,
+    approved: Option<String>,
+    comment_type: Option<String>,
+}
+
+/// Reads the posts of a WordPress eXtended RSS file.
+///
+/// Pages, attachments and other post types are skipped, as are trashed
+/// posts and auto-drafts. Only approved comments are kept; pingbacks and
+/// trackbacks become pings. Pending and private posts are imported as
+/// drafts. Text after `<!--more-->` becomes the extended body.
+pub fn from_wxr<R: io::BufRead>(reader: R) -> Result<Vec<OwnedMTIFEntry>, WXRError> {
+    let mut reader = Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    // Names of the open elements, outermost first.
+    let mut path: Vec<String> = vec![];
+    let mut text = String::new();
+    let mut category_domain: Option<String> = None;
+    let mut item: Option<RawItem> = None;
+    let mut comment: Option<RawComment> = None;
+    let mut item_index = 0;
+    let mut entries = vec![];
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "item" => item = Some(RawItem::default()),
+                    "wp:comment" if item.is_some() => comment = Some(RawComment::default()),
+                    "category" => category_domain = attribute(&e, "domain")?,
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str);
+                let value = std::mem::take(&mut text);
+                match (parent, name.as_str()) {
+                    (_, "item") => {
+                        if let Some(raw_item) = item.take() {
+                            if let Some(entry) = into_entry(item_index, raw_item)? {
+                                entries.push(entry);
+                            }
+                            item_index += 1;
+                        }
+                    }
+                    (Some("item"), "wp:comment") => {
+                        if let (Some(item), Some(comment)) = (&mut item, comment.take()) {
+                            item.comments.push(comment);
+                        }
+                    }
+                    (Some("item"), name) => {
+                        if let Some(item) = &mut item {
+                            set_item_field(item, name, category_domain.as_deref(), value);
+                        }
+                    }
+                    (Some("wp:comment"), name) => {
+                        if let Some(comment) = &mut comment {
+                            set_comment_field(comment, name, value);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(e) => text.push_str(&e.xml10_content()?),
+            Event::CData(e) => text.push_str(&e.xml10_content()?),
+            Event::GeneralRef(e) => match e.resolve_char_ref()? {
+                Some(c) => text.push(c),
+                None => {
+                    let entity = e.decode()?;
+                    match quick_xml::escape::resolve_predefined_entity(&entity) {
+                        Some(resolved) => text.push_str(resolved),
+                        // Not defined in XML itself; kept as written.
+                        None => {
+                            text.push('&');
+                            text.push_str(&entity);
+                            text.push(';');
+                        }
+                    }
+                }
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(entries)
+}
+
+fn element_name(e: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(e.name().as_ref()).into_owned()
+}
+
+fn attribute(e: &BytesStart<'_>, name: &str) -> Result<Option<String>, WXRError> {
+    let attribute = e.try_get_attribute(name).map_err(quick_xml::Error::from)?;
+    Ok(match attribute {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+fn set_item_field(item: &mut RawItem, name: &str, category_domain: Option<&str>, value: String) {
+    let field = match name {
+        "title" => &mut item.title,
+        "dc:creator" => &mut item.creator,
+        "content:encoded" => &mut item.content,
+        "excerpt:encoded" => &mut item.excerpt,
+        "wp:post_date" => &mut item.post_date,
+        "wp:post_date_gmt" => &mut item.post_date_gmt,
+        "wp:comment_status" => &mut item.comment_status,
+        "wp:ping_status" => &mut item.ping_status,
+        "wp:post_name" => &mut item.post_name,
+        "wp:status" => &mut item.status,
+        "wp:post_type" => &mut item.post_type,
+        "category" => {
+            match category_domain {
+                Some("category") => item.categories.push(value),
+                Some("post_tag") => item.tags.push(value),
+                _ => {}
+            }
+            return;
+        }
+        _ => return,
+    };
+    *field = Some(value);
+}
+
+fn set_comment_field(comment: &mut RawComment, name: &str, value: String) {
+    let field = match name {
+        "wp:comment_author" => &mut comment.author,
+        "wp:comment_author_email" => &mut comment.email,
+        "wp:comment_author_url" => &mut comment.url,
+        "wp:comment_author_IP" => &mut comment.ip,
+        "wp:comment_date" => &mut comment.date,
+        "wp:comment_date_gmt" => &mut comment.date_gmt,
+        "wp:comment_content" => &mut comment.content,
+        "wp:comment_approved" => &mut comment.approved,
+        "wp:comment_type" => &mut comment.comment_type,
+        _ => return,
+    };
+    *field = Some(value);
+}
+
+// `None` for items that are not importable posts.
+fn into_entry(index: usize, item: RawItem) -> Result<Option<OwnedMTIFEntry>, WXRError> {
+    if item
+        .post_type
+        .as_deref()
+        .is_some_and(|post_type| post_type != "post")
+    {
+        return Ok(None);
+    }
+    let status = match item.status.as_deref() {
+        Some("trash" | "auto-draft") => return Ok(None),
+        Some("publish") | None => Status::Publish,
+        Some("future") => Status::Future,
+        Some(_) => Status::Draft,
+    };
+    let post_date = item.post_date.unwrap_or_default();
+    let Some(date) = parse_date(&post_date) else {
+        return Err(WXRError::InvalidDate {
+            item: index,
+            value: post_date,
+        });
+    };
+
+    let content = item.content.unwrap_or_default();
+    // The block editor stores HTML as is; the classic editor adds
+    // paragraphs on display, like MT's Convert Breaks.
+    let convert_breaks = if content.contains("<!-- wp:") {
+        OwnedConvertBreaks::None
+    } else {
+        OwnedConvertBreaks::Convert
+    };
+    let (body, extended_body) = split_more(&content);
+
+    let mut comments = vec![];
+    let mut pings = vec![];
+    for comment in item.comments {
+        if comment.approved.as_deref() != Some("1") {
+            continue;
+        }
+        let date = comment.date.as_deref().and_then(parse_date);
+        let utc_offset = utc_offset(date, comment.date_gmt.as_deref());
+        let text = comment.content.unwrap_or_default();
+        match comment.comment_type.as_deref() {
+            Some("pingback" | "trackback") => pings.push(OwnedPing {
+                title: None,
+                url: non_empty(comment.url),
+                ip: non_empty(comment.ip),
+                date,
+                utc_offset,
+                blog_name: non_empty(comment.author),
+                status: None,
+                text,
+            }),
+            _ => comments.push(OwnedComment {
+                author: non_empty(comment.author),
+                email: non_empty(comment.email),
+                url: non_empty(comment.url),
+                ip: non_empty(comment.ip),
+                date,
+                utc_offset,
+                status: None,
+                text,
+            }),
+        }
+    }
+
+    Ok(Some(OwnedMTIFEntry {
+        metadata: OwnedMetaData {
+            author: non_empty(item.creator),
+            title: non_empty(item.title),
+            basename: non_empty(item.post_name),
+            status: Some(status),
+            allow_comments: item.comment_status.as_deref().map(|s| s == "open"),
+            allow_pings: item.ping_status.as_deref().map(|s| s == "open"),
+            convert_breaks: Some(convert_breaks),
+            primary_category: None,
+            category: item.categories,
+            date,
+            utc_offset: utc_offset(Some(date), item.post_date_gmt.as_deref()),
+            no_entry: false,
+            tags: item.tags,
+            image: None,
+            unique_url: None,
+            extra: vec![],
+        },
+        body,
+        extended_body,
+        excerpt: non_empty(item.excerpt),
+        keywords: None,
+        comments,
+        pings,
+        pinged_urls: vec![],
+        extra_sections: vec![],
+    }))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
+
+// YYYY-MM-DD hh:MM:SS
+fn parse_date(value: &str) -> Option<time::PrimitiveDateTime> {
+    time::PrimitiveDateTime::parse(
+        value,
+        time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+    )
+    .ok()
+}
+
+// WXR has no offsets, only local and GMT dates. Drafts have a zero GMT
+// date, in which case the date is taken as UTC.
+fn utc_offset(date: Option<time::PrimitiveDateTime>, date_gmt: Option<&str>) -> time::UtcOffset {
+    let date_gmt = date_gmt.and_then(parse_date);
+    match (date, date_gmt) {
+        (Some(date), Some(date_gmt)) => i32::try_from((date - date_gmt).whole_seconds())
+            .ok()
+            .and_then(|seconds| time::UtcOffset::from_whole_seconds(seconds).ok())
+            .unwrap_or(time::UtcOffset::UTC),
+        _ => time::UtcOffset::UTC,
+    }
+}
+
+// Splits at `<!--more-->`, which may carry custom link text before `-->`.
+fn split_more(content: &str) -> (Option<String>, Option<String>) {
+    let split = content.find("<!--more").and_then(|start| {
+        let end = start + content[start..].find("-->")? + "-->".len();
+        Some((&content[..start], &content[end..]))
+    });
+    match split {
+        Some((body, extended_body)) => (
+            Some(body.trim_end_matches(['\r', '\n']).to_string()),
+            Some(extended_body.trim_start_matches(['\r', '\n']).to_string()),
+        ),
+        None if content.is_empty() => (None, None),
+        None => (Some(content.to_string()), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wxr::WXRWriter;
+    use crate::{MTIFParser, MTIFWriter};
+
+    const CONTENTS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <title>Blog</title>
+  <item>
+    <title>Fish &amp; Chips &#8211; a review</title>
+    <dc:creator><![CDATA[alice]]></dc:creator>
+    <content:encoded><![CDATA[Intro
+<!--more Read on-->
+Rest]]></content:encoded>
+    <excerpt:encoded><![CDATA[]]></excerpt:encoded>
+    <wp:post_date><![CDATA[2020-05-01 18:30:00]]></wp:post_date>
+    <wp:post_date_gmt><![CDATA[2020-05-01 09:30:00]]></wp:post_date_gmt>
+    <wp:comment_status><![CDATA[closed]]></wp:comment_status>
+    <wp:ping_status><![CDATA[open]]></wp:ping_status>
+    <wp:post_name><![CDATA[fish-and-chips]]></wp:post_name>
+    <wp:status><![CDATA[pending]]></wp:status>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+    <category domain="category" nicename="food"><![CDATA[Food]]></category>
+    <category domain="post_tag" nicename="uk"><![CDATA[UK]]></category>
+    <wp:postmeta>
+      <wp:meta_key><![CDATA[_edit_last]]></wp:meta_key>
+      <wp:meta_value><![CDATA[1]]></wp:meta_value>
+    </wp:postmeta>
+    <wp:comment>
+      <wp:comment_author><![CDATA[bob]]></wp:comment_author>
+      <wp:comment_author_email><![CDATA[bob@example.com]]></wp:comment_author_email>
+      <wp:comment_date><![CDATA[2020-05-02 08:00:00]]></wp:comment_date>
+      <wp:comment_date_gmt><![CDATA[2020-05-01 23:00:00]]></wp:comment_date_gmt>
+      <wp:comment_content><![CDATA[Yum]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[1]]></wp:comment_approved>
+      <wp:comment_type><![CDATA[comment]]></wp:comment_type>
+    </wp:comment>
+    <wp:comment>
+      <wp:comment_author><![CDATA[spammer]]></wp:comment_author>
+      <wp:comment_date><![CDATA[2020-05-02 08:00:00]]></wp:comment_date>
+      <wp:comment_content><![CDATA[Buy now]]></wp:comment_content>
+      <wp:comment_approved><![CDATA[spam]]></wp:comment_approved>
+    </wp:comment>
+  </item>
+  <item>
+    <title>Logo</title>
+    <wp:post_date><![CDATA[2020-05-01 18:30:00]]></wp:post_date>
+    <wp:post_type><![CDATA[attachment]]></wp:post_type>
+  </item>
+  <item>
+    <title>Deleted</title>
+    <wp:post_date><![CDATA[2020-05-01 18:30:00]]></wp:post_date>
+    <wp:status><![CDATA[trash]]></wp:status>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+  </item>
+</channel>
+</rss>
+"#;
+
+    #[test]
+    fn test_from_wxr() {
+        let entries = from_wxr(CONTENTS.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        let metadata = &entry.metadata;
+        assert_eq!(metadata.title.as_deref(), Some("Fish & Chips – a review"));
+        assert_eq!(metadata.author.as_deref(), Some("alice"));
+        assert_eq!(metadata.basename.as_deref(), Some("fish-and-chips"));
+        assert_eq!(metadata.status, Some(Status::Draft));
+        assert_eq!(metadata.allow_comments, Some(false));
+        assert_eq!(metadata.allow_pings, Some(true));
+        assert_eq!(metadata.convert_breaks, Some(OwnedConvertBreaks::Convert));
+        assert_eq!(metadata.category, vec!["Food"]);
+        assert_eq!(metadata.tags, vec!["UK"]);
+        assert_eq!(metadata.date, time::macros::datetime!(2020-05-01 18:30:00));
+        assert_eq!(metadata.utc_offset, time::macros::offset!(+9));
+        assert_eq!(entry.body.as_deref(), Some("Intro"));
+        assert_eq!(entry.extended_body.as_deref(), Some("Rest"));
+        assert_eq!(entry.excerpt, None);
+
+        assert_eq!(entry.comments.len(), 1);
+        assert_eq!(entry.comments[0].author.as_deref(), Some("bob"));
+        assert_eq!(entry.comments[0].utc_offset, time::macros::offset!(+9));
+        assert_eq!(entry.comments[0].text, "Yum");
+    }
+
+    #[test]
+    fn test_from_wxr_invalid_date() {
+        let contents = "<rss><channel><item>\
+                        <title>No date</title>\
+                        <wp:post_date>0000-00-00 00:00:00</wp:post_date>\
+                        </item></channel></rss>";
+        let error = from_wxr(contents.as_bytes()).unwrap_err();
+        assert!(matches!(
+            error,
+            WXRError::InvalidDate { item: 0, ref value } if value == "0000-00-00 00:00:00"
+        ));
+
+        assert!(matches!(
+            from_wxr("<rss><item></rss>".as_bytes()),
+            Err(WXRError::Xml(_))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let contents = std::fs::read_to_string("./example/example.txt").unwrap();
+        let entries = MTIFParser::new().parse(&contents).unwrap();
+        let mut wxr = Vec::new();
+        WXRWriter::new().write(&mut wxr, &entries).unwrap();
+
+        let imported = from_wxr(wxr.as_slice()).unwrap();
+        assert_eq!(imported.len(), entries.len());
+        for (entry, imported) in entries.iter().zip(&imported) {
+            assert_eq!(imported.metadata.title.as_deref(), entry.metadata.title);
+            assert_eq!(imported.metadata.date, entry.metadata.date);
+            assert_eq!(imported.body.as_deref(), entry.body);
+            assert_eq!(imported.extended_body.as_deref(), entry.extended_body);
+            assert_eq!(imported.excerpt.as_deref(), entry.excerpt);
+            assert_eq!(imported.comments.len(), entry.comments.len());
+            assert_eq!(imported.pings.len(), entry.pings.len());
+        }
+        assert_eq!(imported[0].pings[0].blog_name.as_deref(), Some("My Weblog"));
+
+        let mut mtif = Vec::new();
+        let borrowed: Vec<_> = imported.iter().map(OwnedMTIFEntry::as_borrowed).collect();
+        MTIFWriter::new().write(&mut mtif, &borrowed).unwrap();
+        let mtif = String::from_utf8(mtif).unwrap();
+        assert_eq!(MTIFParser::new().parse(&mtif).unwrap().len(), entries.len());
+    }
+}
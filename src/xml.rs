@@ -0,0 +1,62 @@
+//! Helpers shared by the XML exporters.
+
+use std::io;
+
+use quick_xml::events::{BytesCData, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+
+pub(crate) fn write_text<W: io::Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    text: &str,
+) -> io::Result<()> {
+    writer
+        .create_element(name)
+        .write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+pub(crate) fn write_cdata<W: io::Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    text: &str,
+) -> io::Result<()> {
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    write_cdata_events(writer, text)?;
+    writer.write_event(Event::End(BytesEnd::new(name)))
+}
+
+// `]]>` cannot appear inside a CDATA section, so it is split across several.
+pub(crate) fn write_cdata_events<W: io::Write>(
+    writer: &mut Writer<W>,
+    text: &str,
+) -> io::Result<()> {
+    for cdata in BytesCData::escaped(text) {
+        writer.write_event(Event::CData(cdata))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn format_error(e: time::error::Format) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+pub(crate) fn rfc2822(date: time::OffsetDateTime) -> io::Result<String> {
+    date.format(&time::format_description::well_known::Rfc2822)
+        .map_err(format_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdata_escaping() {
+        let mut writer = Writer::new(Vec::new());
+        write_cdata(&mut writer, "content:encoded", "a]]>b").unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "<content:encoded><![CDATA[a]]]]><![CDATA[>b]]></content:encoded>"
+        );
+    }
+}